@@ -1,147 +1,1234 @@
-use crate::parser::{self, ASTNode, VariableNode, VariableCallNode, PrintNode};
+use crate::lexer::{Span, Token};
+use crate::parser::{ASTNode, AssignNode, BinaryOpNode, BlockNode, CallNode, ForNode, ForeachNode, FunctionNode, IfNode, IndexNode, InterpolationNode, ListNode, MapNode, OnceNode, Pattern, PrintNode, RangeNode, ReturnNode, UnaryOpNode, UpdateNode, VariableCallNode, VariableNode, WhileNode};
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
 
-pub struct Interpreter
+const MAX_CALL_DEPTH: usize = 10000;
+
+const DEFAULT_STACK_BUDGET: usize = 1024 * 1024;
+
+type SharedPointer = *const ();
+
+#[derive(PartialEq, Eq, Hash)]
+#[derive(Debug)]
+#[derive(Clone)]
+pub enum MapKey
 {
-    pub ast: Vec<ASTNode>,
-    pub context: Vec<(String, String)>,
+    Number(u64),
+    String(String),
+    Bool(bool),
 }
 
-impl Interpreter
+impl MapKey
 {
-    pub fn new(ast: Vec<ASTNode>) -> Interpreter
+    pub fn new(value: Value) -> Result<MapKey, Value>
     {
-        Interpreter { ast, context: Vec::new() }
+        match value
+        {
+            // `-0` and `0` are equal, so they must be the same key.
+            Value::Number(number) => Ok(MapKey::Number(if number == 0.0 { 0 } else { number.to_bits() })),
+            Value::String(string) => Ok(MapKey::String(string)),
+            Value::Bool(boolean) => Ok(MapKey::Bool(boolean)),
+            other => Err(other),
+        }
     }
 
-    fn evaluate(&mut self, node: &ASTNode) -> String
+    pub fn value(&self) -> Value
     {
-        match node
+        match self
         {
-            ASTNode::Number(num_node) =>
-            {
-                num_node.display().to_string()
-            }
-            ASTNode::String(str_node) =>
+            MapKey::Number(bits) => Value::Number(f64::from_bits(*bits)),
+            MapKey::String(string) => Value::String(string.clone()),
+            MapKey::Bool(boolean) => Value::Bool(*boolean),
+        }
+    }
+}
+
+/// Map entries in insertion order, with a hashed index from each key to its position.
+#[derive(Default)]
+pub struct MapEntries
+{
+    entries: Vec<(MapKey, Value)>,
+    positions: HashMap<MapKey, usize>,
+}
+
+impl MapEntries
+{
+    pub fn new() -> MapEntries
+    {
+        MapEntries::default()
+    }
+
+    pub fn get(&self, key: &MapKey) -> Option<&Value>
+    {
+        self.positions.get(key).map(|&position| &self.entries[position].1)
+    }
+
+    pub fn insert(&mut self, key: MapKey, value: Value)
+    {
+        match self.positions.get(&key)
+        {
+            Some(&position) => self.entries[position].1 = value,
+            None =>
             {
-                str_node.display().to_string()
+                self.positions.insert(key.clone(), self.entries.len());
+                self.entries.push((key, value));
             }
-            ASTNode::UnaryOp(un_op_node) =>
-            {
-                let operator = un_op_node.operator.value.clone();
-                let value = self.evaluate(&*un_op_node.node);
+        }
+    }
+
+    pub fn len(&self) -> usize
+    {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool
+    {
+        self.entries.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &(MapKey, Value)>
+    {
+        self.entries.iter()
+    }
+}
+
+#[derive(Clone)]
+pub enum Value
+{
+    Number(f64),
+    String(String),
+    Bool(bool),
+    Range { start: f64, end: f64, step: f64, inclusive: bool },
+    Function(Rc<Closure>),
+    List(Rc<RefCell<Vec<Value>>>),
+    Map(Rc<RefCell<MapEntries>>),
+    Void,
+}
+
+impl Value
+{
+    pub fn type_name(&self) -> &'static str
+    {
+        match self
+        {
+            Value::Number(_) => "Number",
+            Value::String(_) => "String",
+            Value::Bool(_) => "Bool",
+            Value::Range { .. } => "Range",
+            Value::Function(_) => "Function",
+            Value::List(_) => "List",
+            Value::Map(_) => "Map",
+            Value::Void => "Void",
+        }
+    }
 
-                if operator == "-"
+    /// `false`, `0`, the empty string, the empty list, the empty map and `void` are falsy; every other value is truthy.
+    pub fn is_truthy(&self) -> bool
+    {
+        match self
+        {
+            Value::Number(number) => *number != 0.0,
+            Value::String(string) => !string.is_empty(),
+            Value::Bool(boolean) => *boolean,
+            Value::Range { .. } => true,
+            Value::Function(_) => true,
+            Value::List(items) => !items.borrow().is_empty(),
+            Value::Map(entries) => !entries.borrow().is_empty(),
+            Value::Void => false,
+        }
+    }
+
+    /// Quotes strings and prints a collection that contains itself as `[...]` or `{...}`.
+    fn write_nested(&self, f: &mut fmt::Formatter, visited: &mut Vec<SharedPointer>) -> fmt::Result
+    {
+        match self
+        {
+            Value::String(string) => write!(f, "\"{}\"", string),
+            Value::List(items) =>
+            {
+                let pointer = Rc::as_ptr(items).cast::<()>();
+                if visited.contains(&pointer)
                 {
-                    let num_value: f32 = value.parse().unwrap();
-                    (-num_value).to_string()
+                    return write!(f, "[...]");
                 }
-                else
+                visited.push(pointer);
+                write!(f, "[")?;
+                for (position, item) in items.borrow().iter().enumerate()
                 {
-                    panic!("Unsupported unary operator");
+                    if position > 0
+                    {
+                        write!(f, ", ")?;
+                    }
+                    item.write_nested(f, visited)?;
                 }
+                visited.pop();
+                write!(f, "]")
             }
-            ASTNode::BinaryOp(bin_op_node) =>
+            Value::Map(entries) =>
             {
-                let left_value = self.evaluate(&*bin_op_node.left);
-                let right_value = self.evaluate(&*bin_op_node.right);
-                let operator = bin_op_node.operator.value.clone();
-
-                if operator == "+"
+                let pointer = Rc::as_ptr(entries).cast::<()>();
+                if visited.contains(&pointer)
                 {
-                    if let (Ok(left_num), Ok(right_num)) = (left_value.parse::<f32>(), right_value.parse::<f32>())
-                    {
-                        (left_num + right_num).to_string()
-                    }
-                    else
+                    return write!(f, "{{...}}");
+                }
+                visited.push(pointer);
+                write!(f, "{{")?;
+                for (position, (key, value)) in entries.borrow().iter().enumerate()
+                {
+                    if position > 0
                     {
-                        format!("{}{}", left_value, right_value)
+                        write!(f, ", ")?;
                     }
+                    key.value().write_nested(f, visited)?;
+                    write!(f, ": ")?;
+                    value.write_nested(f, visited)?;
                 }
-                else if operator == "-"
+                visited.pop();
+                write!(f, "}}")
+            }
+            _ => write!(f, "{}", self),
+        }
+    }
+
+    /// Treats a pair of collections that is already being compared as equal.
+    fn equals(&self, other: &Value, visited: &mut Vec<(SharedPointer, SharedPointer)>) -> bool
+    {
+        match (self, other)
+        {
+            (Value::Number(left), Value::Number(right)) => left == right,
+            (Value::String(left), Value::String(right)) => left == right,
+            (Value::Bool(left), Value::Bool(right)) => left == right,
+            (
+                Value::Range { start, end, step, inclusive },
+                Value::Range { start: other_start, end: other_end, step: other_step, inclusive: other_inclusive },
+            ) => start == other_start && end == other_end && step == other_step && inclusive == other_inclusive,
+            (Value::Function(left), Value::Function(right)) => left == right,
+            (Value::List(left), Value::List(right)) =>
+            {
+                if Rc::ptr_eq(left, right)
                 {
-                    let left_num: f32 = left_value.parse().unwrap();
-                    let right_num: f32 = right_value.parse().unwrap();
-                    (left_num - right_num).to_string()
+                    return true;
                 }
-                else if operator == "*"
+                let pair = (Rc::as_ptr(left).cast::<()>(), Rc::as_ptr(right).cast::<()>());
+                if visited.contains(&pair)
                 {
-                    let left_num: f32 = left_value.parse().unwrap();
-                    let right_num: f32 = right_value.parse().unwrap();
-                    (left_num * right_num).to_string()
+                    return true;
                 }
-                else if operator == "/"
+                visited.push(pair);
+                let (left, right) = (left.borrow(), right.borrow());
+                let equal = left.len() == right.len() && left.iter().zip(right.iter()).all(|(left, right)| left.equals(right, visited));
+                visited.pop();
+                equal
+            }
+            (Value::Map(left), Value::Map(right)) =>
+            {
+                if Rc::ptr_eq(left, right)
                 {
-                    let left_num: f32 = left_value.parse().unwrap();
-                    let right_num: f32 = right_value.parse().unwrap();
-                    if right_num == 0.0
-                    {
-                        panic!("Error: Division by zero");
-                    }
-                    else
-                    {
-                        (left_num / right_num).to_string()
-                    }
+                    return true;
                 }
-                else
+                let pair = (Rc::as_ptr(left).cast::<()>(), Rc::as_ptr(right).cast::<()>());
+                if visited.contains(&pair)
                 {
-                    panic!("Unsupported binary operator");
+                    return true;
                 }
+                visited.push(pair);
+                let (left, right) = (left.borrow(), right.borrow());
+                let equal = left.len() == right.len() && left.iter().all(|(key, value)|
+                {
+                    right.get(key).is_some_and(|other| value.equals(other, visited))
+                });
+                visited.pop();
+                equal
             }
-            ASTNode::Variable(var_node) =>
+            (Value::Void, Value::Void) => true,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Value
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        match self
+        {
+            Value::Number(number) => write!(f, "{}", number),
+            Value::String(string) => write!(f, "{}", string),
+            Value::Bool(boolean) => write!(f, "{}", boolean),
+            Value::Range { start, end, step, inclusive } =>
             {
-                let var_name = var_node.name.clone();
-                let value = self.evaluate(&*var_node.value);
-                let mut variable_exists = false;
-                for (name, _) in &mut self.context
+                let operator = if *inclusive { "..=" } else { ".." };
+                write!(f, "{}{}{}", start, operator, end)?;
+                if *step != 1.0
                 {
-                    if *name == var_name
+                    write!(f, " step {}", step)?;
+                }
+                Ok(())
+            }
+            Value::Function(closure) => match &closure.function.name
+            {
+                Some(name) => write!(f, "<function {}>", name),
+                None => write!(f, "<function>"),
+            },
+            Value::List(_) | Value::Map(_) => self.write_nested(f, &mut Vec::new()),
+            Value::Void => write!(f, "void"),
+        }
+    }
+}
+
+impl fmt::Debug for Value
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        self.write_nested(f, &mut Vec::new())
+    }
+}
+
+impl PartialEq for Value
+{
+    fn eq(&self, other: &Value) -> bool
+    {
+        self.equals(other, &mut Vec::new())
+    }
+}
+
+pub type EnvRef = Rc<RefCell<Environment>>;
+
+pub struct Environment
+{
+    variables: Vec<(String, Value)>,
+    parent: Option<EnvRef>,
+}
+
+impl Environment
+{
+    pub fn new(parent: Option<EnvRef>) -> EnvRef
+    {
+        Rc::new(RefCell::new(Environment { variables: Vec::new(), parent }))
+    }
+
+    pub fn get(&self, name: &str) -> Option<Value>
+    {
+        match self.variables.iter().rev().find(|(variable, _)| variable == name)
+        {
+            Some((_, value)) => Some(value.clone()),
+            None => self.parent.as_ref()?.borrow().get(name),
+        }
+    }
+
+    pub fn assign(&mut self, name: &str, value: Value) -> bool
+    {
+        if let Some((_, slot)) = self.variables.iter_mut().rev().find(|(variable, _)| variable == name)
+        {
+            *slot = value;
+            return true;
+        }
+        match &self.parent
+        {
+            Some(parent) => parent.borrow_mut().assign(name, value),
+            None => false,
+        }
+    }
+
+    pub fn define(&mut self, name: String, value: Value)
+    {
+        match self.variables.iter_mut().find(|(variable, _)| *variable == name)
+        {
+            Some((_, slot)) => *slot = value,
+            None => self.variables.push((name, value)),
+        }
+    }
+}
+
+pub struct Closure
+{
+    pub function: Rc<FunctionNode>,
+    pub environment: EnvRef,
+}
+
+impl PartialEq for Closure
+{
+    fn eq(&self, other: &Closure) -> bool
+    {
+        Rc::ptr_eq(&self.function, &other.function) && Rc::ptr_eq(&self.environment, &other.environment)
+    }
+}
+
+impl fmt::Debug for Closure
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        write!(f, "Closure({})", self.function.title())
+    }
+}
+
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone)]
+pub enum RuntimeErrorKind
+{
+    UndefinedVariable(String),
+    UndeclaredAssignment(String),
+    UnsupportedOperand { operator: String, operand: &'static str },
+    UnsupportedOperands { operator: String, left: &'static str, right: &'static str },
+    DivisionByZero,
+    ZeroStep,
+    NotIterable(&'static str),
+    CannotDestructure { type_name: &'static str, count: usize },
+    NotCallable(&'static str),
+    ArgumentCount { function: String, expected: usize, found: usize },
+    MissingReturn(String),
+    StackOverflow,
+    NestingTooDeep,
+    NotIndexable(&'static str),
+    InvalidIndex(&'static str),
+    FractionalIndex(f64),
+    IndexOutOfBounds { index: f64, length: usize },
+    SliceAssignment,
+    InvalidKey(&'static str),
+    MissingKey(String),
+    OnceReentered,
+}
+
+impl fmt::Display for RuntimeErrorKind
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        match self
+        {
+            RuntimeErrorKind::UndefinedVariable(name) => write!(f, "Undefined variable '{}'", name),
+            RuntimeErrorKind::UndeclaredAssignment(name) => write!(f, "Cannot assign to undeclared variable '{}'", name),
+            RuntimeErrorKind::UnsupportedOperand { operator, operand } => write!(f, "Cannot apply '{}' to {}", operator, operand),
+            RuntimeErrorKind::UnsupportedOperands { operator, left, right } => write!(f, "Cannot apply '{}' to {} and {}", operator, left, right),
+            RuntimeErrorKind::DivisionByZero => write!(f, "Division by zero"),
+            RuntimeErrorKind::ZeroStep => write!(f, "Range step cannot be zero"),
+            RuntimeErrorKind::NotIterable(type_name) => write!(f, "Cannot iterate over {}", type_name),
+            RuntimeErrorKind::CannotDestructure { type_name, count } => write!(f, "Cannot destructure {} into {} names", type_name, count),
+            RuntimeErrorKind::NotCallable(type_name) => write!(f, "Cannot call {}", type_name),
+            RuntimeErrorKind::ArgumentCount { function, expected, found } => write!(f, "Expected {} arguments for {}, but got {}", expected, function, found),
+            RuntimeErrorKind::MissingReturn(function) => write!(f, "Reached the end of {} without returning a value", function),
+            RuntimeErrorKind::StackOverflow => write!(f, "Maximum call depth of {} exceeded", MAX_CALL_DEPTH),
+            RuntimeErrorKind::NestingTooDeep => write!(f, "Evaluation nests too deeply for the interpreter's stack"),
+            RuntimeErrorKind::NotIndexable(type_name) => write!(f, "Cannot index into {}", type_name),
+            RuntimeErrorKind::InvalidIndex(type_name) => write!(f, "Cannot index a list with {}", type_name),
+            RuntimeErrorKind::FractionalIndex(index) => write!(f, "List index {} is not a whole number", index),
+            RuntimeErrorKind::IndexOutOfBounds { index, length } => write!(f, "Index {} is out of bounds for a list of length {}", index, length),
+            RuntimeErrorKind::SliceAssignment => write!(f, "Cannot assign to a slice"),
+            RuntimeErrorKind::InvalidKey(type_name) => write!(f, "Cannot use {} as a map key", type_name),
+            RuntimeErrorKind::MissingKey(key) => write!(f, "Key {} is not in the map", key),
+            RuntimeErrorKind::OnceReentered => write!(f, "Once block entered again before it finished"),
+        }
+    }
+}
+
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct RuntimeError
+{
+    pub kind: RuntimeErrorKind,
+    pub span: Span,
+}
+
+impl RuntimeError
+{
+    pub fn new(kind: RuntimeErrorKind, span: Span) -> RuntimeError
+    {
+        RuntimeError { kind, span }
+    }
+}
+
+impl fmt::Display for RuntimeError
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        write!(f, "{} at {}", self.kind, self.span)
+    }
+}
+
+pub enum Unwind
+{
+    Error(Box<RuntimeError>),
+    Break(Option<String>),
+    Continue(Option<String>),
+    Return(Value),
+}
+
+impl From<RuntimeError> for Unwind
+{
+    fn from(error: RuntimeError) -> Unwind
+    {
+        Unwind::Error(Box::new(error))
+    }
+}
+
+fn targets_loop(label: &Option<String>, loop_label: &Option<String>) -> bool
+{
+    label.is_none() || label == loop_label
+}
+
+pub enum Item
+{
+    Value(Value),
+    Entry(Value, Value),
+}
+
+impl Item
+{
+    pub fn into_value(self) -> Value
+    {
+        match self
+        {
+            Item::Value(value) => value,
+            Item::Entry(key, value) => Value::List(Rc::new(RefCell::new(vec![key, value]))),
+        }
+    }
+}
+
+pub type ItemIter = Box<dyn Iterator<Item = Item>>;
+
+struct RangeIter
+{
+    start: f64,
+    end: f64,
+    step: f64,
+    inclusive: bool,
+    index: f64,
+}
+
+impl Iterator for RangeIter
+{
+    type Item = Item;
+
+    fn next(&mut self) -> Option<Item>
+    {
+        let value = self.start + self.index * self.step;
+        let in_range = if self.step > 0.0 { value < self.end } else { value > self.end };
+        if !(in_range || (self.inclusive && value == self.end))
+        {
+            return None;
+        }
+        self.index += 1.0;
+        Some(Item::Value(Value::Number(value)))
+    }
+}
+
+pub fn iterate(value: &Value, span: Span) -> Result<ItemIter, RuntimeError>
+{
+    match value
+    {
+        Value::Range { start, end, step, inclusive } =>
+        {
+            Ok(Box::new(RangeIter { start: *start, end: *end, step: *step, inclusive: *inclusive, index: 0.0 }))
+        }
+        Value::List(items) =>
+        {
+            Ok(Box::new(items.borrow().clone().into_iter().map(Item::Value)))
+        }
+        Value::Map(entries) =>
+        {
+            let pairs: Vec<Item> = entries.borrow().iter().map(|(key, value)| Item::Entry(key.value(), value.clone())).collect();
+            Ok(Box::new(pairs.into_iter()))
+        }
+        Value::String(string) =>
+        {
+            let characters: Vec<Item> = string.chars().map(|c| Item::Value(Value::String(c.to_string()))).collect();
+            Ok(Box::new(characters.into_iter()))
+        }
+        _ => Err(RuntimeError::new(RuntimeErrorKind::NotIterable(value.type_name()), span)),
+    }
+}
+
+fn destructure(pattern: &Pattern, item: Item, span: Span) -> Result<Vec<(String, Value)>, RuntimeError>
+{
+    let names = match pattern
+    {
+        Pattern::Name(name) => return Ok(vec![(name.clone(), item.into_value())]),
+        Pattern::Tuple(names) => names,
+    };
+    let cannot_destructure = |type_name| RuntimeError::new(RuntimeErrorKind::CannotDestructure { type_name, count: names.len() }, span);
+    let values = match item
+    {
+        Item::Entry(key, value) => vec![key, value],
+        Item::Value(Value::List(items)) => items.borrow().clone(),
+        Item::Value(other) => return Err(cannot_destructure(other.type_name())),
+    };
+    if values.len() != names.len()
+    {
+        return Err(cannot_destructure("List"));
+    }
+    Ok(names.iter().cloned().zip(values).collect())
+}
+
+fn element_index(index: f64, length: usize, span: Span) -> Result<usize, RuntimeError>
+{
+    if index.fract() != 0.0
+    {
+        return Err(RuntimeError::new(RuntimeErrorKind::FractionalIndex(index), span));
+    }
+    let position = if index < 0.0 { index + length as f64 } else { index };
+    if position < 0.0 || position >= length as f64
+    {
+        return Err(RuntimeError::new(RuntimeErrorKind::IndexOutOfBounds { index, length }, span));
+    }
+    Ok(position as usize)
+}
+
+fn map_key(key: Value, span: Span) -> Result<MapKey, RuntimeError>
+{
+    MapKey::new(key).map_err(|other| RuntimeError::new(RuntimeErrorKind::InvalidKey(other.type_name()), span))
+}
+
+fn missing_key(key: &MapKey, span: Span) -> RuntimeError
+{
+    RuntimeError::new(RuntimeErrorKind::MissingKey(format!("{:?}", key.value())), span)
+}
+
+fn slice(items: &[Value], range: &Value, span: Span) -> Result<Value, RuntimeError>
+{
+    let Value::Range { start, end, step, inclusive } = *range else
+    {
+        return Err(RuntimeError::new(RuntimeErrorKind::InvalidIndex(range.type_name()), span));
+    };
+    let bound = |position: f64| if position < 0.0 { position + items.len() as f64 } else { position };
+    let positions = Value::Range { start: bound(start), end: bound(end), step, inclusive };
+    let mut slice = Vec::new();
+    for position in iterate(&positions, span)?
+    {
+        if let Item::Value(Value::Number(position)) = position
+        {
+            if position < 0.0
+            {
+                return Err(RuntimeError::new(RuntimeErrorKind::IndexOutOfBounds { index: position, length: items.len() }, span));
+            }
+            slice.push(items[element_index(position, items.len(), span)?].clone());
+        }
+    }
+    Ok(Value::List(Rc::new(RefCell::new(slice))))
+}
+
+enum Place
+{
+    Variable(String, Span),
+    Element(Rc<RefCell<Vec<Value>>>, usize, Span),
+    Entry(Rc<RefCell<MapEntries>>, MapKey, Span),
+}
+
+fn unsupported_operands(operator: &Token, left: &Value, right: &Value) -> RuntimeError
+{
+    let kind = RuntimeErrorKind::UnsupportedOperands
+    {
+        operator: operator.value.clone(),
+        left: left.type_name(),
+        right: right.type_name()
+    };
+    RuntimeError::new(kind, operator.span)
+}
+
+fn compare(operator: &Token, left: &Value, right: &Value) -> Result<Ordering, RuntimeError>
+{
+    let ordering = match (left, right)
+    {
+        (Value::Number(left_num), Value::Number(right_num)) => left_num.partial_cmp(right_num),
+        (Value::String(left_str), Value::String(right_str)) => Some(left_str.cmp(right_str)),
+        _ => None,
+    };
+    ordering.ok_or_else(|| unsupported_operands(operator, left, right))
+}
+
+fn binary_op(operator: &Token, left: Value, right: Value) -> Result<Value, RuntimeError>
+{
+    match operator.value.as_str()
+    {
+        "==" => return Ok(Value::Bool(left == right)),
+        "!=" => return Ok(Value::Bool(left != right)),
+        "<" => return Ok(Value::Bool(compare(operator, &left, &right)? == Ordering::Less)),
+        ">" => return Ok(Value::Bool(compare(operator, &left, &right)? == Ordering::Greater)),
+        "<=" => return Ok(Value::Bool(compare(operator, &left, &right)? != Ordering::Greater)),
+        ">=" => return Ok(Value::Bool(compare(operator, &left, &right)? != Ordering::Less)),
+        _ => {}
+    }
+    match (&left, &right)
+    {
+        (Value::Number(left_num), Value::Number(right_num)) =>
+        {
+            let (left_num, right_num) = (*left_num, *right_num);
+            match operator.value.as_str()
+            {
+                "+" => Ok(Value::Number(left_num + right_num)),
+                "-" => Ok(Value::Number(left_num - right_num)),
+                "*" => Ok(Value::Number(left_num * right_num)),
+                "**" => Ok(Value::Number(left_num.powf(right_num))),
+                "/" if right_num == 0.0 => Err(RuntimeError::new(RuntimeErrorKind::DivisionByZero, operator.span)),
+                "/" => Ok(Value::Number(left_num / right_num)),
+                _ => Err(unsupported_operands(operator, &left, &right)),
+            }
+        }
+        (Value::String(_), _) | (_, Value::String(_)) if operator.value == "+" =>
+        {
+            Ok(Value::String(format!("{}{}", left, right)))
+        }
+        _ => Err(unsupported_operands(operator, &left, &right)),
+    }
+}
+
+pub struct Interpreter
+{
+    pub ast: Vec<ASTNode>,
+    pub globals: EnvRef,
+    pub stack_budget: usize,
+    environment: EnvRef,
+    depth: usize,
+    stack_base: usize,
+    once_values: HashMap<usize, Option<Value>>,
+}
+
+fn stack_position() -> usize
+{
+    let marker = 0u8;
+    std::hint::black_box(&marker) as *const u8 as usize
+}
+
+impl Interpreter
+{
+    pub fn new(ast: Vec<ASTNode>) -> Interpreter
+    {
+        let globals = Environment::new(None);
+        Interpreter
+        {
+            ast,
+            globals: Rc::clone(&globals),
+            stack_budget: DEFAULT_STACK_BUDGET,
+            environment: globals,
+            depth: 0,
+            stack_base: 0,
+            once_values: HashMap::new()
+        }
+    }
+
+    fn in_environment<T>(&mut self, environment: EnvRef, run: impl FnOnce(&mut Interpreter) -> T) -> T
+    {
+        let previous = std::mem::replace(&mut self.environment, environment);
+        let result = run(self);
+        self.environment = previous;
+        result
+    }
+
+    fn resolve_place(&mut self, target: &ASTNode) -> Result<Place, Unwind>
+    {
+        match target
+        {
+            ASTNode::VariableCall(var_call_node) => Ok(Place::Variable(var_call_node.name.clone(), var_call_node.span)),
+            ASTNode::Index(index_node) =>
+            {
+                let span = index_node.index.span();
+                let items = match self.evaluate(&index_node.target)?
+                {
+                    Value::List(items) => items,
+                    Value::Map(entries) =>
                     {
-                        variable_exists = true;
-                        break;
+                        let key = map_key(self.evaluate(&index_node.index)?, span)?;
+                        return Ok(Place::Entry(entries, key, span));
                     }
-                }
-                
-                if !variable_exists
+                    other => return Err(RuntimeError::new(RuntimeErrorKind::NotIndexable(other.type_name()), index_node.target.span()).into()),
+                };
+                let position = match self.evaluate(&index_node.index)?
+                {
+                    Value::Number(index) => element_index(index, items.borrow().len(), span)?,
+                    Value::Range { .. } => return Err(RuntimeError::new(RuntimeErrorKind::SliceAssignment, span).into()),
+                    other => return Err(RuntimeError::new(RuntimeErrorKind::InvalidIndex(other.type_name()), span).into()),
+                };
+                Ok(Place::Element(items, position, span))
+            }
+            _ => unreachable!("the parser rejects invalid assignment targets"),
+        }
+    }
+
+    fn read_place(&self, place: &Place) -> Result<Value, RuntimeError>
+    {
+        match place
+        {
+            Place::Variable(name, span) => self.environment.borrow().get(name)
+                .ok_or_else(|| RuntimeError::new(RuntimeErrorKind::UndefinedVariable(name.clone()), *span)),
+            Place::Element(items, position, span) =>
+            {
+                let items = items.borrow();
+                items.get(*position).cloned().ok_or_else(||
                 {
-                    self.context.push((var_name.clone(), value.clone()));
+                    RuntimeError::new(RuntimeErrorKind::IndexOutOfBounds { index: *position as f64, length: items.len() }, *span)
+                })
+            }
+            Place::Entry(entries, key, span) => entries.borrow().get(key).cloned().ok_or_else(|| missing_key(key, *span)),
+        }
+    }
+
+    fn write_place(&mut self, place: &Place, value: Value) -> Result<(), RuntimeError>
+    {
+        match place
+        {
+            Place::Variable(name, span) =>
+            {
+                if !self.environment.borrow_mut().assign(name, value)
+                {
+                    return Err(RuntimeError::new(RuntimeErrorKind::UndeclaredAssignment(name.clone()), *span));
                 }
-                var_name
+                Ok(())
             }
-            ASTNode::VariableCall(var_call_node) =>
+            Place::Element(items, position, span) =>
             {
-                let var_name = var_call_node.name.clone();
-                let mut value = String::new();
-                for (name, val) in &self.context
+                let mut items = items.borrow_mut();
+                let length = items.len();
+                let Some(slot) = items.get_mut(*position) else
                 {
-                    if *name == var_name
-                    {
-                        value = val.clone();
-                        break;
-                    }
+                    return Err(RuntimeError::new(RuntimeErrorKind::IndexOutOfBounds { index: *position as f64, length }, *span));
+                };
+                *slot = value;
+                Ok(())
+            }
+            Place::Entry(entries, key, _) =>
+            {
+                entries.borrow_mut().insert(key.clone(), value);
+                Ok(())
+            }
+        }
+    }
+
+    fn call_function(&mut self, closure: Rc<Closure>, arguments: Vec<Value>, span: Span) -> Result<Value, Unwind>
+    {
+        let function = &closure.function;
+        if arguments.len() != function.parameters.len()
+        {
+            let kind = RuntimeErrorKind::ArgumentCount
+            {
+                function: function.title(),
+                expected: function.parameters.len(),
+                found: arguments.len()
+            };
+            return Err(RuntimeError::new(kind, span).into());
+        }
+        if self.depth >= MAX_CALL_DEPTH
+        {
+            return Err(RuntimeError::new(RuntimeErrorKind::StackOverflow, span).into());
+        }
+        let environment = Environment::new(Some(Rc::clone(&closure.environment)));
+        for (parameter, argument) in function.parameters.iter().zip(arguments)
+        {
+            environment.borrow_mut().define(parameter.clone(), argument);
+        }
+        self.depth += 1;
+        let result = self.in_environment(environment, |interpreter| interpreter.evaluate(&function.body));
+        self.depth -= 1;
+        match result
+        {
+            Err(Unwind::Return(value)) => Ok(value),
+            Ok(_) if function.is_void => Ok(Value::Void),
+            Ok(_) => Err(RuntimeError::new(RuntimeErrorKind::MissingReturn(function.title()), span).into()),
+            Err(unwind) => Err(unwind),
+        }
+    }
+
+    fn evaluate(&mut self, node: &ASTNode) -> Result<Value, Unwind>
+    {
+        if self.stack_base.abs_diff(stack_position()) > self.stack_budget
+        {
+            return Err(RuntimeError::new(RuntimeErrorKind::NestingTooDeep, node.span()).into());
+        }
+        self.evaluate_node(node)
+    }
+
+    fn evaluate_node(&mut self, node: &ASTNode) -> Result<Value, Unwind>
+    {
+        match node
+        {
+            ASTNode::Number(num_node) =>
+            {
+                Ok(Value::Number(num_node.value.parse().unwrap()))
+            }
+            ASTNode::String(str_node) =>
+            {
+                Ok(Value::String(str_node.value.clone()))
+            }
+            ASTNode::Bool(bool_node) =>
+            {
+                Ok(Value::Bool(bool_node.value))
+            }
+            ASTNode::Interpolation(node) => self.evaluate_interpolation(node),
+            ASTNode::UnaryOp(node) => self.evaluate_unary_op(node),
+            ASTNode::BinaryOp(node) => self.evaluate_binary_op(node),
+            ASTNode::Variable(node) => self.evaluate_variable(node),
+            ASTNode::VariableCall(node) => self.evaluate_variable_call(node),
+            ASTNode::Print(node) => self.evaluate_print(node),
+            ASTNode::Block(node) => self.evaluate_block(node),
+            ASTNode::If(node) => self.evaluate_if(node),
+            ASTNode::While(node) => self.evaluate_while(node),
+            ASTNode::Range(node) => self.evaluate_range(node),
+            ASTNode::For(node) => self.evaluate_for(node),
+            ASTNode::Foreach(node) => self.evaluate_foreach(node),
+            ASTNode::Break(jump_node) =>
+            {
+                Err(Unwind::Break(jump_node.label.clone()))
+            }
+            ASTNode::Continue(jump_node) =>
+            {
+                Err(Unwind::Continue(jump_node.label.clone()))
+            }
+            ASTNode::Function(node) => self.evaluate_function(node),
+            ASTNode::Call(node) => self.evaluate_call(node),
+            ASTNode::Return(node) => self.evaluate_return(node),
+            ASTNode::Assign(node) => self.evaluate_assign(node),
+            ASTNode::Update(node) => self.evaluate_update(node),
+            ASTNode::List(node) => self.evaluate_list(node),
+            ASTNode::Map(node) => self.evaluate_map(node),
+            ASTNode::Index(node) => self.evaluate_index(node),
+            ASTNode::Once(node) => self.evaluate_once(node),
+        }
+    }
+
+    fn evaluate_interpolation(&mut self, interpolation_node: &InterpolationNode) -> Result<Value, Unwind>
+    {
+        let mut value = String::new();
+        for part in &interpolation_node.parts
+        {
+            value.push_str(&self.evaluate(part)?.to_string());
+        }
+        Ok(Value::String(value))
+    }
+
+    fn evaluate_unary_op(&mut self, un_op_node: &UnaryOpNode) -> Result<Value, Unwind>
+    {
+        let operator = &un_op_node.operator;
+        let value = self.evaluate(&un_op_node.node)?;
+        match (operator.value.as_str(), value)
+        {
+            ("-", Value::Number(number)) => Ok(Value::Number(-number)),
+            ("not", value) => Ok(Value::Bool(!value.is_truthy())),
+            (_, value) =>
+            {
+                let kind = RuntimeErrorKind::UnsupportedOperand
+                {
+                    operator: operator.value.clone(),
+                    operand: value.type_name()
+                };
+                Err(RuntimeError::new(kind, operator.span).into())
+            }
+        }
+    }
+
+    fn evaluate_binary_op(&mut self, bin_op_node: &BinaryOpNode) -> Result<Value, Unwind>
+    {
+        let left_value = self.evaluate(&bin_op_node.left)?;
+        match bin_op_node.operator.value.as_str()
+        {
+            "and" if !left_value.is_truthy() => return Ok(Value::Bool(false)),
+            "or" if left_value.is_truthy() => return Ok(Value::Bool(true)),
+            "and" | "or" => return Ok(Value::Bool(self.evaluate(&bin_op_node.right)?.is_truthy())),
+            _ => {}
+        }
+        let right_value = self.evaluate(&bin_op_node.right)?;
+        Ok(binary_op(&bin_op_node.operator, left_value, right_value)?)
+    }
+
+    fn evaluate_variable(&mut self, var_node: &VariableNode) -> Result<Value, Unwind>
+    {
+        let value = self.evaluate(&var_node.value)?;
+        self.environment.borrow_mut().define(var_node.name.clone(), value.clone());
+        Ok(value)
+    }
+
+    fn evaluate_variable_call(&mut self, var_call_node: &VariableCallNode) -> Result<Value, Unwind>
+    {
+        if let Some(value) = self.environment.borrow().get(&var_call_node.name)
+        {
+            return Ok(value);
+        }
+        let kind = RuntimeErrorKind::UndefinedVariable(var_call_node.name.clone());
+        Err(RuntimeError::new(kind, var_call_node.span).into())
+    }
+
+    fn evaluate_print(&mut self, print_node: &PrintNode) -> Result<Value, Unwind>
+    {
+        let value = self.evaluate(&print_node.node)?;
+        println!("{}", value);
+        Ok(value)
+    }
+
+    fn evaluate_block(&mut self, block_node: &BlockNode) -> Result<Value, Unwind>
+    {
+        let environment = Environment::new(Some(Rc::clone(&self.environment)));
+        self.in_environment(environment, |interpreter|
+        {
+            let mut value = Value::Void;
+            for statement in &block_node.statements
+            {
+                value = interpreter.evaluate(statement)?;
+            }
+            Ok(value)
+        })
+    }
+
+    fn evaluate_if(&mut self, if_node: &IfNode) -> Result<Value, Unwind>
+    {
+        for (condition, body) in &if_node.branches
+        {
+            if self.evaluate(condition)?.is_truthy()
+            {
+                return self.evaluate(body);
+            }
+        }
+        match &if_node.else_branch
+        {
+            Some(else_branch) => self.evaluate(else_branch),
+            None => Ok(Value::Void),
+        }
+    }
+
+    fn evaluate_while(&mut self, while_node: &WhileNode) -> Result<Value, Unwind>
+    {
+        while self.evaluate(&while_node.condition)?.is_truthy()
+        {
+            if !self.run_loop_body(&while_node.body, &while_node.label)?
+            {
+                break;
+            }
+        }
+        Ok(Value::Void)
+    }
+
+    fn evaluate_range(&mut self, range_node: &RangeNode) -> Result<Value, Unwind>
+    {
+        let operator = &range_node.operator;
+        let start = self.evaluate(&range_node.start)?;
+        let end = self.evaluate(&range_node.end)?;
+        let (Value::Number(start), Value::Number(end)) = (&start, &end) else
+        {
+            return Err(unsupported_operands(operator, &start, &end).into());
+        };
+        let step = match &range_node.step
+        {
+            Some(step) => match self.evaluate(step)?
+            {
+                Value::Number(step) => step,
+                other =>
+                {
+                    let kind = RuntimeErrorKind::UnsupportedOperand { operator: "step".to_string(), operand: other.type_name() };
+                    return Err(RuntimeError::new(kind, step.span()).into());
                 }
-                value
+            },
+            None => 1.0,
+        };
+        if step == 0.0
+        {
+            return Err(RuntimeError::new(RuntimeErrorKind::ZeroStep, range_node.span).into());
+        }
+        Ok(Value::Range { start: *start, end: *end, step, inclusive: range_node.inclusive })
+    }
+
+    fn evaluate_for(&mut self, for_node: &ForNode) -> Result<Value, Unwind>
+    {
+        let iterable = self.evaluate(&for_node.iterable)?;
+        if !matches!(iterable, Value::Range { .. })
+        {
+            let kind = RuntimeErrorKind::NotIterable(iterable.type_name());
+            return Err(RuntimeError::new(kind, for_node.iterable.span()).into());
+        }
+        let span = for_node.iterable.span();
+        let items = iterate(&iterable, span)?;
+        let pattern = Pattern::Name(for_node.variable.clone());
+        self.run_iteration(items, &pattern, &for_node.body, &for_node.label, span)
+    }
+
+    fn evaluate_foreach(&mut self, foreach_node: &ForeachNode) -> Result<Value, Unwind>
+    {
+        let iterable = self.evaluate(&foreach_node.iterable)?;
+        let span = foreach_node.iterable.span();
+        let items = iterate(&iterable, span)?;
+        self.run_iteration(items, &foreach_node.pattern, &foreach_node.body, &foreach_node.label, span)
+    }
+
+    fn evaluate_function(&mut self, function_node: &Rc<FunctionNode>) -> Result<Value, Unwind>
+    {
+        let closure = Closure { function: Rc::clone(function_node), environment: Rc::clone(&self.environment) };
+        let value = Value::Function(Rc::new(closure));
+        match &function_node.name
+        {
+            Some(name) =>
+            {
+                self.environment.borrow_mut().define(name.clone(), value);
+                Ok(Value::Void)
             }
-            ASTNode::Print(print_node) =>
+            None => Ok(value),
+        }
+    }
+
+    fn evaluate_call(&mut self, call_node: &CallNode) -> Result<Value, Unwind>
+    {
+        let callee = self.evaluate(&call_node.callee)?;
+        let Value::Function(closure) = callee else
+        {
+            return Err(RuntimeError::new(RuntimeErrorKind::NotCallable(callee.type_name()), call_node.callee.span()).into());
+        };
+        let mut arguments = Vec::new();
+        for argument in &call_node.arguments
+        {
+            arguments.push(self.evaluate(argument)?);
+        }
+        self.call_function(closure, arguments, call_node.span)
+    }
+
+    fn evaluate_return(&mut self, return_node: &ReturnNode) -> Result<Value, Unwind>
+    {
+        let value = match &return_node.value
+        {
+            Some(value) => self.evaluate(value)?,
+            None => Value::Void,
+        };
+        Err(Unwind::Return(value))
+    }
+
+    fn evaluate_assign(&mut self, assign_node: &AssignNode) -> Result<Value, Unwind>
+    {
+        let operator = &assign_node.operator;
+        let place = self.resolve_place(&assign_node.target)?;
+        let value = if operator.value == "="
+        {
+            self.evaluate(&assign_node.value)?
+        }
+        else
+        {
+            let current = self.read_place(&place)?;
+            let right = self.evaluate(&assign_node.value)?;
+            let mut arithmetic = operator.clone();
+            arithmetic.value.pop();
+            binary_op(&arithmetic, current, right)?
+        };
+        self.write_place(&place, value.clone())?;
+        Ok(value)
+    }
+
+    fn evaluate_update(&mut self, update_node: &UpdateNode) -> Result<Value, Unwind>
+    {
+        let operator = &update_node.operator;
+        let place = self.resolve_place(&update_node.target)?;
+        let current = self.read_place(&place)?;
+        let Value::Number(number) = current else
+        {
+            let kind = RuntimeErrorKind::UnsupportedOperand { operator: operator.value.clone(), operand: current.type_name() };
+            return Err(RuntimeError::new(kind, operator.span).into());
+        };
+        let updated = if operator.value == "++" { number + 1.0 } else { number - 1.0 };
+        self.write_place(&place, Value::Number(updated))?;
+        Ok(Value::Number(if update_node.prefix { updated } else { number }))
+    }
+
+    fn evaluate_list(&mut self, list_node: &ListNode) -> Result<Value, Unwind>
+    {
+        let mut items = Vec::new();
+        for element in &list_node.elements
+        {
+            items.push(self.evaluate(element)?);
+        }
+        Ok(Value::List(Rc::new(RefCell::new(items))))
+    }
+
+    fn evaluate_map(&mut self, map_node: &MapNode) -> Result<Value, Unwind>
+    {
+        let mut entries = MapEntries::new();
+        for (key, value) in &map_node.entries
+        {
+            let key_value = map_key(self.evaluate(key)?, key.span())?;
+            let value = self.evaluate(value)?;
+            entries.insert(key_value, value);
+        }
+        Ok(Value::Map(Rc::new(RefCell::new(entries))))
+    }
+
+    fn evaluate_index(&mut self, index_node: &IndexNode) -> Result<Value, Unwind>
+    {
+        let span = index_node.index.span();
+        let items = match self.evaluate(&index_node.target)?
+        {
+            Value::List(items) => items,
+            Value::Map(entries) =>
             {
-                let value = self.evaluate(&*print_node.node);
-                println!("{}", value);
-                value
+                let key = map_key(self.evaluate(&index_node.index)?, span)?;
+                return Ok(entries.borrow().get(&key).cloned().ok_or_else(|| missing_key(&key, span))?);
             }
-            _ =>
+            other => return Err(RuntimeError::new(RuntimeErrorKind::NotIndexable(other.type_name()), index_node.target.span()).into()),
+        };
+        let index = self.evaluate(&index_node.index)?;
+        let items = items.borrow();
+        match index
+        {
+            Value::Number(index) => Ok(items[element_index(index, items.len(), span)?].clone()),
+            range @ Value::Range { .. } => Ok(slice(&items, &range, span)?),
+            other => Err(RuntimeError::new(RuntimeErrorKind::InvalidIndex(other.type_name()), span).into()),
+        }
+    }
+
+    fn evaluate_once(&mut self, once_node: &OnceNode) -> Result<Value, Unwind>
+    {
+        match self.once_values.get(&once_node.id)
+        {
+            Some(Some(value)) => return Ok(value.clone()),
+            Some(None) => return Err(RuntimeError::new(RuntimeErrorKind::OnceReentered, once_node.span).into()),
+            None => {}
+        }
+        self.once_values.insert(once_node.id, None);
+        let result = self.evaluate(&once_node.body);
+        match &result
+        {
+            Ok(value) => self.once_values.insert(once_node.id, Some(value.clone())),
+            Err(_) => self.once_values.remove(&once_node.id),
+        };
+        result
+    }
+
+    fn run_iteration(&mut self, items: ItemIter, pattern: &Pattern, body: &ASTNode, label: &Option<String>, span: Span) -> Result<Value, Unwind>
+    {
+        for item in items
+        {
+            let environment = Environment::new(Some(Rc::clone(&self.environment)));
+            for (name, value) in destructure(pattern, item, span)?
+            {
+                environment.borrow_mut().define(name, value);
+            }
+            if !self.in_environment(environment, |interpreter| interpreter.run_loop_body(body, label))?
             {
-                panic!("Unsupported AST node");
+                break;
             }
         }
+        Ok(Value::Void)
     }
 
-    pub fn interpret(&mut self) -> Vec<String>
+    fn run_loop_body(&mut self, body: &ASTNode, label: &Option<String>) -> Result<bool, Unwind>
+    {
+        match self.evaluate(body)
+        {
+            Err(Unwind::Break(target)) if targets_loop(&target, label) => Ok(false),
+            Err(Unwind::Continue(target)) if targets_loop(&target, label) => Ok(true),
+            Err(unwind) => Err(unwind),
+            Ok(_) => Ok(true),
+        }
+    }
+
+    pub fn interpret(&mut self) -> Result<Vec<Value>, RuntimeError>
     {
         let mut results = Vec::new();
+        self.stack_base = stack_position();
         for node in self.ast.clone()
         {
-            let result = self.evaluate(&node);
-            results.push(result);
+            match self.evaluate(&node)
+            {
+                Ok(result) => results.push(result),
+                Err(Unwind::Error(error)) => return Err(*error),
+                Err(Unwind::Break(_)) | Err(Unwind::Continue(_)) => unreachable!("the parser rejects jumps outside of loops"),
+                Err(Unwind::Return(_)) => unreachable!("the parser rejects return outside of functions"),
+            }
         }
-        results
+        Ok(results)
     }
 }
@@ -1,9 +1,12 @@
+use std::collections::VecDeque;
+use std::fmt;
+
 pub static KEYWORDS: &[&str] =
 &[
     "var", "for", "in", "foreach", "if", "else",
     "elseif", "return", "void", "while", "once",
     "import", "define", "and", "not", "or", "true",
-    "false", "break", "print"
+    "false", "break", "continue", "print"
 ];
 
 pub static BLOCKDELIMITERS: [char; 6] =
@@ -13,7 +16,7 @@ pub static BLOCKDELIMITERS: [char; 6] =
 
 pub static PUNCTUATION:  &[char] = 
 &[
-    '.', ','
+    '.', ',', ';', ':'
 ];
 
 pub static OPERATORS: &[&str] =
@@ -21,9 +24,53 @@ pub static OPERATORS: &[&str] =
     "+" , "-" , "/" , "*" , "=" ,
     "+=", "-=", "/=", "*=", "==",
     "<", ">", "<=", ">=", "!=",
-    "++", "--"
+    "++", "--", "**", "..", "..="
 ];
 
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+#[derive(Default)]
+pub struct Span
+{
+    pub line: usize,
+    pub column: usize,
+    pub start: usize,
+    pub end: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+}
+
+impl Span
+{
+    pub fn new(line: usize, column: usize, start: usize, end: usize, end_line: usize, end_column: usize) -> Span
+    {
+        Span { line, column, start, end, end_line, end_column }
+    }
+
+    pub fn merge(&self, other: Span) -> Span
+    {
+        let (first, last) = if self.start <= other.start { (*self, other) } else { (other, *self) };
+        Span
+        {
+            line: first.line,
+            column: first.column,
+            start: first.start,
+            end: first.end.max(last.end),
+            end_line: if last.end > first.end { last.end_line } else { first.end_line },
+            end_column: if last.end > first.end { last.end_column } else { first.end_column },
+        }
+    }
+}
+
+impl fmt::Display for Span
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
 #[derive(PartialEq)]
 #[derive(Debug)]
 #[derive(Clone)]
@@ -31,33 +78,69 @@ pub enum TypeOfToken
 {
     NUMBER,
     STRING,
+    INTERPOLATIONSTART,
+    INTERPOLATIONEND,
     IDENTIFIER,
     KEYWORD,
     OPERATOR,
     PUNCTUATION,
     BLOCKDELIMITERS,
     NEWLINE,
+    DOCCOMMENT,
     WHITESPACE,
+    ERROR,
     EOF
 }
 
-impl TypeOfToken
+impl fmt::Display for TypeOfToken
 {
-    pub fn to_string(&self) -> String 
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
     {
-        match self 
+        let name = match self 
         {
-            TypeOfToken::NUMBER => "Number".to_string(),
-            TypeOfToken::STRING => "String".to_string(),
-            TypeOfToken::IDENTIFIER => "Identifier".to_string(),
-            TypeOfToken::KEYWORD => "Keyword".to_string(),
-            TypeOfToken::OPERATOR => "Operator".to_string(),
-            TypeOfToken::PUNCTUATION => "Punctuation".to_string(),
-            TypeOfToken::BLOCKDELIMITERS => "Block Delimiters".to_string(),
-            TypeOfToken::NEWLINE => "Newline".to_string(),
-            TypeOfToken::WHITESPACE => "Whitespace".to_string(),
-            TypeOfToken::EOF => "EOF".to_string(),
-        }
+            TypeOfToken::NUMBER => "Number",
+            TypeOfToken::STRING => "String",
+            TypeOfToken::INTERPOLATIONSTART => "Interpolation Start",
+            TypeOfToken::INTERPOLATIONEND => "Interpolation End",
+            TypeOfToken::IDENTIFIER => "Identifier",
+            TypeOfToken::KEYWORD => "Keyword",
+            TypeOfToken::OPERATOR => "Operator",
+            TypeOfToken::PUNCTUATION => "Punctuation",
+            TypeOfToken::BLOCKDELIMITERS => "Block Delimiters",
+            TypeOfToken::NEWLINE => "Newline",
+            TypeOfToken::DOCCOMMENT => "Doc Comment",
+            TypeOfToken::WHITESPACE => "Whitespace",
+            TypeOfToken::ERROR => "Error",
+            TypeOfToken::EOF => "EOF",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone)]
+pub enum TriviaKind
+{
+    Whitespace,
+    Comment,
+    LineContinuation,
+}
+
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct Trivia
+{
+    pub kind: TriviaKind,
+    pub text: String,
+    pub span: Span,
+}
+
+impl Trivia
+{
+    pub fn new(kind: TriviaKind, text: String, span: Span) -> Trivia
+    {
+        Trivia { kind, text, span }
     }
 }
 
@@ -66,44 +149,214 @@ pub struct Token
 {
     pub tot: TypeOfToken,
     pub value: String,
+    pub span: Span,
+    pub text: String,
+    pub leading_trivia: Vec<Trivia>,
 }
 
 impl Token 
 {
-    pub fn new(tot: TypeOfToken, value: String) -> Token 
+    pub fn new(tot: TypeOfToken, value: String, span: Span) -> Token 
     {
-        Token { tot, value }
+        Token { tot, value, span, text: String::new(), leading_trivia: Vec::new() }
     }
+}
+
+pub fn reconstruct(tokens: &[Token]) -> String
+{
+    let mut src = String::new();
+    for token in tokens 
+    {
+        for trivia in &token.leading_trivia 
+        {
+            src.push_str(&trivia.text);
+        }
+        src.push_str(&token.text);
+    }
+    src
+}
+
+impl fmt::Display for Token
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        write!(f, "Token: {:?}, Value: {}, At: {}", self.tot, self.value, self.span)
+    }
+}
+
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone)]
+pub enum LexErrorKind
+{
+    UnknownCharacter(char),
+    UnterminatedString,
+    UnterminatedComment,
+    MalformedNumber(String),
+    NumberTooLarge(String),
+    InvalidEscape(String),
+}
 
-    pub fn to_string(&self) -> String 
+impl fmt::Display for LexErrorKind
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
     {
-        format!("Token: {:?}, Value: {}", self.tot, self.value)
+        match self 
+        {
+            LexErrorKind::UnknownCharacter(c) => write!(f, "Unknown character '{}'", c),
+            LexErrorKind::UnterminatedString => write!(f, "Unterminated string literal"),
+            LexErrorKind::UnterminatedComment => write!(f, "Unterminated block comment"),
+            LexErrorKind::MalformedNumber(number) => write!(f, "Malformed number '{}'", number),
+            LexErrorKind::NumberTooLarge(number) => write!(f, "Number '{}' does not fit in 64 bits", number),
+            LexErrorKind::InvalidEscape(escape) => write!(f, "Invalid escape sequence '{}'", escape),
+        }
     }
 }
 
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct LexError
+{
+    pub kind: LexErrorKind,
+    pub span: Span,
+}
+
+impl LexError
+{
+    pub fn new(kind: LexErrorKind, span: Span) -> LexError
+    {
+        LexError { kind, span }
+    }
+}
+
+impl fmt::Display for LexError
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        write!(f, "{} at {}", self.kind, self.span)
+    }
+}
+
+fn strip_separators(digits: &str, radix: u32) -> Option<String>
+{
+    let chars: Vec<char> = digits.chars().collect();
+    for (i, c) in chars.iter().enumerate() 
+    {
+        let separated = *c == '_' 
+            && i > 0 
+            && i + 1 < chars.len() 
+            && chars[i - 1].is_digit(radix) 
+            && chars[i + 1].is_digit(radix);
+        if !c.is_digit(radix) && !separated 
+        {
+            return None;
+        }
+    }
+    if chars.is_empty() 
+    {
+        return None;
+    }
+    Some(digits.replace('_', ""))
+}
+
+fn malformed(number: &str) -> LexErrorKind
+{
+    LexErrorKind::MalformedNumber(number.to_string())
+}
+
+fn normalize_number(number: &str) -> Result<String, LexErrorKind>
+{
+    let prefixed = [("0x", 16), ("0X", 16), ("0b", 2), ("0B", 2), ("0o", 8), ("0O", 8)];
+    for (prefix, radix) in prefixed 
+    {
+        if let Some(digits) = number.strip_prefix(prefix) 
+        {
+            let digits = strip_separators(digits, radix).ok_or_else(|| malformed(number))?;
+            return u64::from_str_radix(&digits, radix)
+                .map(|value| value.to_string())
+                .map_err(|_| LexErrorKind::NumberTooLarge(number.to_string()));
+        }
+    }
+    let (mantissa, exponent) = match number.find(['e', 'E']) 
+    {
+        Some(index) => (&number[..index], Some(&number[index + 1..])),
+        None => (number, None),
+    };
+    let (integer, fraction) = match mantissa.split_once('.') 
+    {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (mantissa, None),
+    };
+    let mut value = strip_separators(integer, 10).ok_or_else(|| malformed(number))?;
+    if let Some(fraction) = fraction 
+    {
+        value.push('.');
+        value.push_str(&strip_separators(fraction, 10).ok_or_else(|| malformed(number))?);
+    }
+    match exponent 
+    {
+        Some(exponent) => 
+        {
+            let unsigned = exponent.strip_prefix(['+', '-']).unwrap_or(exponent);
+            strip_separators(unsigned, 10).ok_or_else(|| malformed(number))?;
+            let sign = if exponent.starts_with('-') { "-" } else { "" };
+            let scientific = format!("{}e{}{}", value, sign, unsigned.replace('_', ""));
+            scientific.parse::<f64>().map(|value| value.to_string()).map_err(|_| malformed(number))
+        }
+        None => Ok(value),
+    }
+}
+
+enum StringPart
+{
+    Complete(String),
+    Interpolated(String),
+}
+
+struct Interpolation
+{
+    triple: bool,
+    depth: usize,
+    opening: (usize, usize, usize),
+}
+
 pub struct Lexer 
 {
     src: String,
     index: usize,
     line: usize,
-    column: usize
+    column: usize,
+    interpolations: Vec<Interpolation>,
+    pending: VecDeque<Result<Token, LexError>>,
+    lossless: bool,
+    trivia: Vec<Trivia>,
 }
 
 impl Lexer
 {
     pub fn new(src: String) -> Lexer
     {
-        Lexer { src, index: 0, line: 1, column: 1 }
+        Lexer 
+        { 
+            src, 
+            index: 0, 
+            line: 1, 
+            column: 1, 
+            interpolations: Vec::new(), 
+            pending: VecDeque::new(), 
+            lossless: false, 
+            trivia: Vec::new() 
+        }
     }
 
     pub fn next_char(&mut self) -> char
     {
-        if self.src[self.index..].is_empty() 
+        let Some((_, c)) = self.src[self.index..].char_indices().next() else 
         {
             return '\0';
-        }
-        let c = self.src[self.index..].chars().next().unwrap();
-        self.index += 1;
+        };
+        self.index += c.len_utf8();
         if c == '\n' 
         {
             self.line += 1;
@@ -118,96 +371,479 @@ impl Lexer
 
     pub fn peek_next_char(&self) -> char
     {
-        if self.src[self.index..].is_empty() 
+        self.peek_nth_char(0)
+    }
+
+    fn peek_nth_char(&self, n: usize) -> char
+    {
+        self.src[self.index..].char_indices().nth(n).map_or('\0', |(_, c)| c)
+    }
+
+    fn span_from(&self, start: usize, line: usize, column: usize) -> Span
+    {
+        Span::new(line, column, start, self.index, self.line, self.column)
+    }
+
+    fn match_operator(&self, start: usize) -> Option<&'static str>
+    {
+        let rest = &self.src[start..];
+        OPERATORS.iter()
+            .filter(|operator| rest.starts_with(*operator))
+            .max_by_key(|operator| operator.len())
+            .copied()
+    }
+
+    fn read_number(&mut self, first: char) -> String
+    {
+        let mut number = String::new();
+        number.push(first);
+        let decimal = !(first == '0' && matches!(self.peek_next_char(), 'x' | 'X' | 'b' | 'B' | 'o' | 'O'));
+        loop 
         {
-            return '\0';
+            let c = self.peek_next_char();
+            let last = number.chars().last().unwrap_or(first);
+            let continues = c.is_alphanumeric() 
+                || c == '_' 
+                || (decimal && c == '.' && self.peek_nth_char(1) != '.')
+                || (decimal && (c == '+' || c == '-') && (last == 'e' || last == 'E'));
+            if !continues 
+            {
+                break;
+            }
+            number.push(self.next_char());
         }
-        self.src[self.index..].chars().next().unwrap()
+        number
     }
 
-    pub fn tokenize(&mut self) -> Vec<Token>
+    fn open_string(&mut self) -> bool
     {
-        let mut tokens = Vec::new();
-        while self.index < self.src.len() 
+        let triple = self.src[self.index..].starts_with("\"\"");
+        if triple 
         {
-            let c = self.next_char();
-            if c.is_whitespace() 
+            self.next_char();
+            self.next_char();
+            if self.peek_next_char() == '\n' 
             {
-                continue;
-            } 
-            else if c == '\n'
+                self.next_char();
+            }
+        }
+        triple
+    }
+
+    fn close_string(&mut self, triple: bool)
+    {
+        let quotes = if triple { 3 } else { 1 };
+        for _ in 0..quotes 
+        {
+            self.next_char();
+        }
+    }
+
+    fn read_string(&mut self, raw: bool, triple: bool, opening: (usize, usize, usize)) -> Result<StringPart, LexError>
+    {
+        let mut string = String::new();
+        loop 
+        {
+            let c = self.peek_next_char();
+            if c == '\0' || (c == '\n' && !triple) 
+            {
+                // Strings this one is interpolated into end here too, so they are not reported again.
+                self.interpolations.clear();
+                let (start, line, column) = opening;
+                return Err(LexError::new(LexErrorKind::UnterminatedString, self.span_from(start, line, column)));
+            }
+            if (triple && self.src[self.index..].starts_with("\"\"\"")) || (!triple && c == '"') 
+            {
+                return Ok(StringPart::Complete(string));
+            }
+            if c == '{' && !raw 
+            {
+                return Ok(StringPart::Interpolated(string));
+            }
+            let (start, line, column) = (self.index, self.line, self.column);
+            self.next_char();
+            if c == '\\' && !raw 
+            {
+                match self.read_escape() 
+                {
+                    Ok(escaped) => string.push(escaped),
+                    Err(kind) => self.fail(LexError::new(kind, self.span_from(start, line, column))),
+                }
+            }
+            else 
+            {
+                string.push(c);
+            }
+        }
+    }
+
+    fn push_segment(&mut self, string: String, segment: (usize, usize, usize))
+    {
+        let (start, line, column) = segment;
+        if self.index > start 
+        {
+            self.emit(Token::new(TypeOfToken::STRING, string, self.span_from(start, line, column)));
+        }
+    }
+
+    fn open_interpolation(&mut self, triple: bool, opening: (usize, usize, usize))
+    {
+        let (start, line, column) = (self.index, self.line, self.column);
+        self.next_char();
+        self.emit(Token::new(TypeOfToken::BLOCKDELIMITERS, "{".to_string(), self.span_from(start, line, column)));
+        self.interpolations.push(Interpolation { triple, depth: 0, opening });
+    }
+
+    fn resume_string(&mut self, interpolation: Interpolation) -> Result<(), LexError>
+    {
+        let segment = (self.index, self.line, self.column);
+        match self.read_string(false, interpolation.triple, interpolation.opening)? 
+        {
+            StringPart::Complete(string) => 
             {
-                tokens.push(Token::new(TypeOfToken::NEWLINE, "newline".to_string()));
+                self.push_segment(string, segment);
+                let (start, line, column) = (self.index, self.line, self.column);
+                self.close_string(interpolation.triple);
+                self.emit(Token::new(TypeOfToken::INTERPOLATIONEND, "\"".to_string(), self.span_from(start, line, column)));
             }
-            else if c.is_alphabetic() || c == '_' 
+            StringPart::Interpolated(string) => 
             {
-                let mut identifier = String::new();
-                identifier.push(c);
-                while self.peek_next_char().is_alphanumeric() || self.peek_next_char() == '_' 
+                self.push_segment(string, segment);
+                self.open_interpolation(interpolation.triple, interpolation.opening);
+            }
+        }
+        Ok(())
+    }
+
+    fn read_escape(&mut self) -> Result<char, LexErrorKind>
+    {
+        let c = self.peek_next_char();
+        if c == '\n' || c == '\0' 
+        {
+            return Err(LexErrorKind::InvalidEscape("\\".to_string()));
+        }
+        self.next_char();
+        match c 
+        {
+            'n' => Ok('\n'),
+            't' => Ok('\t'),
+            'r' => Ok('\r'),
+            '0' => Ok('\0'),
+            '"' => Ok('"'),
+            '\\' => Ok('\\'),
+            '{' => Ok('{'),
+            '}' => Ok('}'),
+            'u' => 
+            {
+                let mut escape = String::from("\\u");
+                if self.peek_next_char() != '{' 
                 {
-                    identifier.push(self.next_char());
+                    return Err(LexErrorKind::InvalidEscape(escape));
                 }
-                if KEYWORDS.contains(&identifier.as_str()) 
+                escape.push(self.next_char());
+                let mut digits = String::new();
+                while self.peek_next_char().is_ascii_hexdigit() 
                 {
-                    tokens.push(Token::new(TypeOfToken::KEYWORD, identifier));
-                } 
-                else 
+                    digits.push(self.next_char());
+                }
+                escape.push_str(&digits);
+                if self.peek_next_char() != '}' 
                 {
-                    tokens.push(Token::new(TypeOfToken::IDENTIFIER, identifier));
+                    return Err(LexErrorKind::InvalidEscape(escape));
                 }
+                escape.push(self.next_char());
+                u32::from_str_radix(&digits, 16).ok()
+                    .and_then(char::from_u32)
+                    .ok_or(LexErrorKind::InvalidEscape(escape))
+            }
+            _ => Err(LexErrorKind::InvalidEscape(format!("\\{}", c))),
+        }
+    }
+
+    fn skip_block_comment(&mut self) -> bool
+    {
+        self.next_char();
+        let mut depth = 1;
+        while depth > 0 
+        {
+            let rest = &self.src[self.index..];
+            if rest.is_empty() 
+            {
+                return false;
+            }
+            if rest.starts_with("#[") 
+            {
+                depth += 1;
+                self.next_char();
+            }
+            else if rest.starts_with("]#") 
+            {
+                depth -= 1;
+                self.next_char();
+            }
+            self.next_char();
+        }
+        true
+    }
+
+    fn skip_trivia(&mut self, kind: TriviaKind, start: usize, line: usize, column: usize)
+    {
+        if !self.lossless 
+        {
+            return;
+        }
+        let span = self.span_from(start, line, column);
+        let merges = kind == TriviaKind::Whitespace 
+            && self.trivia.last().is_some_and(|last| last.kind == TriviaKind::Whitespace && last.span.end == start);
+        if merges 
+        {
+            let last = self.trivia.last_mut().unwrap();
+            last.text.push_str(&self.src[start..self.index]);
+            last.span = last.span.merge(span);
+            return;
+        }
+        self.trivia.push(Trivia::new(kind, self.src[start..self.index].to_string(), span));
+    }
+
+    pub fn tokenize_lossless(&mut self) -> (Vec<Token>, Vec<LexError>)
+    {
+        self.lossless = true;
+        let mut tokens = Vec::new();
+        let mut errors = Vec::new();
+        for result in self.by_ref() 
+        {
+            match result 
+            {
+                Ok(token) => tokens.push(token),
+                Err(error) => errors.push(error),
+            }
+        }
+        let mut eof = Token::new(TypeOfToken::EOF, "".to_string(), self.span_from(self.index, self.line, self.column));
+        eof.leading_trivia = std::mem::take(&mut self.trivia);
+        tokens.push(eof);
+        (tokens, errors)
+    }
+
+    fn emit(&mut self, mut token: Token)
+    {
+        if self.lossless 
+        {
+            token.leading_trivia = std::mem::take(&mut self.trivia);
+            token.text = self.src[token.span.start..token.span.end].to_string();
+        }
+        self.pending.push_back(Ok(token));
+    }
+
+    fn fail(&mut self, error: LexError)
+    {
+        self.pending.push_back(Err(error));
+    }
+
+    fn fail_token(&mut self, error: LexError, start: usize, line: usize, column: usize)
+    {
+        let message = error.kind.to_string();
+        self.fail(error);
+        if self.lossless 
+        {
+            self.emit(Token::new(TypeOfToken::ERROR, message, self.span_from(start, line, column)));
+        }
+    }
+
+    fn scan_token(&mut self)
+    {
+        let (start, line, column) = (self.index, self.line, self.column);
+        let c = self.next_char();
+        if c.is_whitespace() && c != '\n' 
+        {
+            self.skip_trivia(TriviaKind::Whitespace, start, line, column);
+            return;
+        }
+        if c == '\n'
+        {
+            self.emit(Token::new(TypeOfToken::NEWLINE, "newline".to_string(), self.span_from(start, line, column)));
+        }
+        else if c == '\\' && self.src[self.index..].trim_start_matches([' ', '\t', '\r']).starts_with('\n') 
+        {
+            while self.next_char() != '\n' {}
+            self.skip_trivia(TriviaKind::LineContinuation, start, line, column);
+        }
+        else if (c.is_alphabetic() || c == '_') && !(c == 'r' && self.peek_next_char() == '"') 
+        {
+            let mut identifier = String::new();
+            identifier.push(c);
+            while self.peek_next_char().is_alphanumeric() || self.peek_next_char() == '_' 
+            {
+                identifier.push(self.next_char());
+            }
+            if KEYWORDS.contains(&identifier.as_str()) 
+            {
+                self.emit(Token::new(TypeOfToken::KEYWORD, identifier, self.span_from(start, line, column)));
             } 
-            else if c.is_digit(10) 
+            else 
             {
-                let mut number = String::new();
-                number.push(c);
-                let mut has_decimal_point = false;
-                while self.peek_next_char().is_digit(10) || (self.peek_next_char() == '.' && !has_decimal_point) 
+                self.emit(Token::new(TypeOfToken::IDENTIFIER, identifier, self.span_from(start, line, column)));
+            }
+        } 
+        else if c.is_ascii_digit() 
+        {
+            let number = self.read_number(c);
+            match normalize_number(&number) 
+            {
+                Ok(value) => self.emit(Token::new(TypeOfToken::NUMBER, value, self.span_from(start, line, column))),
+                Err(kind) => self.fail_token(LexError::new(kind, self.span_from(start, line, column)), start, line, column),
+            }
+        }
+        else if c == '"' || (c == 'r' && self.peek_next_char() == '"') 
+        {
+            let raw = c == 'r';
+            if raw 
+            {
+                self.next_char();
+            }
+            let triple = self.open_string();
+            let quote = self.span_from(start, line, column);
+            let segment = (self.index, self.line, self.column);
+            match self.read_string(raw, triple, (start, line, column)) 
+            {
+                Ok(StringPart::Complete(string)) => 
                 {
-                    let next_char = self.next_char();
-                    if next_char == '.' 
-                    {
-                        has_decimal_point = true;
-                    }
-                    number.push(next_char);
+                    self.close_string(triple);
+                    self.emit(Token::new(TypeOfToken::STRING, string, self.span_from(start, line, column)));
                 }
-                tokens.push(Token::new(TypeOfToken::NUMBER, number));
+                Ok(StringPart::Interpolated(string)) => 
+                {
+                    self.emit(Token::new(TypeOfToken::INTERPOLATIONSTART, "\"".to_string(), quote));
+                    self.push_segment(string, segment);
+                    self.open_interpolation(triple, (start, line, column));
+                }
+                Err(error) => self.fail_token(error, start, line, column),
             }
-            else if c == '"' 
+        } 
+        else if let Some(operator) = self.match_operator(start) 
+        {
+            for _ in 1..operator.len() 
+            {
+                self.next_char();
+            }
+            self.emit(Token::new(TypeOfToken::OPERATOR, operator.to_string(), self.span_from(start, line, column)));
+        } 
+        else if PUNCTUATION.contains(&c) 
+        {
+            self.emit(Token::new(TypeOfToken::PUNCTUATION, c.to_string(), self.span_from(start, line, column)));
+        } 
+        else if BLOCKDELIMITERS.contains(&c) 
+        {
+            self.emit(Token::new(TypeOfToken::BLOCKDELIMITERS, c.to_string(), self.span_from(start, line, column)));
+            if let Some(interpolation) = self.interpolations.last_mut() 
             {
-                let mut string = String::new();
-                while self.peek_next_char() != '"' && self.peek_next_char() != '\0' 
+                if c == '{' 
                 {
-                    string.push(self.next_char());
+                    interpolation.depth += 1;
                 }
-                if self.peek_next_char() == '"' 
+                else if c == '}' && interpolation.depth > 0 
                 {
-                    self.next_char(); 
-                    tokens.push(Token::new(TypeOfToken::STRING, string));
+                    interpolation.depth -= 1;
                 }
-            } 
-            else if PUNCTUATION.contains(&c) 
+                else if c == '}' 
+                {
+                    let interpolation = self.interpolations.pop().unwrap();
+                    let (start, line, column) = (self.index, self.line, self.column);
+                    if let Err(error) = self.resume_string(interpolation) 
+                    {
+                        self.fail_token(error, start, line, column);
+                    }
+                }
+            }
+        } 
+        else if c == '#' && self.peek_next_char() == '[' 
+        {
+            if self.skip_block_comment() 
             {
-                tokens.push(Token::new(TypeOfToken::PUNCTUATION, c.to_string()));
-            } 
-            else if OPERATORS.contains(&c.to_string().as_str()) 
+                self.skip_trivia(TriviaKind::Comment, start, line, column);
+            }
+            else 
             {
-                tokens.push(Token::new(TypeOfToken::OPERATOR, c.to_string()));
-            } 
-            else if BLOCKDELIMITERS.contains(&c) 
+                self.fail_token(LexError::new(LexErrorKind::UnterminatedComment, self.span_from(start, line, column)), start, line, column);
+            }
+        }
+        else if c == '#' 
+        {
+            let mut comment = String::new();
+            comment.push(c);
+            while self.peek_next_char() != '\n' && self.peek_next_char() != '\0' 
             {
-                tokens.push(Token::new(TypeOfToken::BLOCKDELIMITERS, c.to_string()));
-            } 
-            else if c == '#' 
+                comment.push(self.next_char());
+            }
+            let starts_line = self.src[..start].rsplit('\n').next().unwrap_or("").trim().is_empty();
+            if let Some(doc) = comment.strip_prefix("##").filter(|_| starts_line) 
             {
-                let mut comment = String::new();
-                comment.push(c);
-                while self.peek_next_char() != '\n' && self.peek_next_char() != '\0' 
-                {
-                    comment.push(self.next_char());
-                }
-                continue; 
+                let doc = doc.strip_prefix(' ').unwrap_or(doc).trim_end().to_string();
+                self.emit(Token::new(TypeOfToken::DOCCOMMENT, doc, self.span_from(start, line, column)));
+            }
+            else 
+            {
+                self.skip_trivia(TriviaKind::Comment, start, line, column);
+            }
+        }
+        else 
+        {
+            self.fail_token(LexError::new(LexErrorKind::UnknownCharacter(c), self.span_from(start, line, column)), start, line, column);
+        }
+    }
+
+    fn finish(&mut self)
+    {
+        let interpolations: Vec<Interpolation> = self.interpolations.drain(..).collect();
+        for interpolation in interpolations 
+        {
+            let (start, line, column) = interpolation.opening;
+            self.fail(LexError::new(LexErrorKind::UnterminatedString, self.span_from(start, line, column)));
+        }
+    }
+
+    pub fn peek(&mut self) -> Option<&Result<Token, LexError>>
+    {
+        while self.pending.is_empty() && self.index < self.src.len() 
+        {
+            self.scan_token();
+        }
+        if self.pending.is_empty() 
+        {
+            self.finish();
+        }
+        self.pending.front()
+    }
+
+    pub fn tokenize(&mut self) -> Result<Vec<Token>, Vec<LexError>>
+    {
+        let mut tokens = Vec::new();
+        let mut errors = Vec::new();
+        for result in self.by_ref() 
+        {
+            match result 
+            {
+                Ok(token) => tokens.push(token),
+                Err(error) => errors.push(error),
             }
         }
-        tokens
+        if errors.is_empty() 
+        {
+            Ok(tokens)
+        }
+        else 
+        {
+            Err(errors)
+        }
+    }
+}
+
+impl Iterator for Lexer
+{
+    type Item = Result<Token, LexError>;
+
+    fn next(&mut self) -> Option<Self::Item>
+    {
+        self.peek();
+        self.pending.pop_front()
     }
 }
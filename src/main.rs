@@ -2,6 +2,10 @@ use lema::interpreter::{self};
 use lema::lexer::{self};
 use lema::parser::{self};
 use std::env;
+use std::thread;
+
+const INTERPRETER_STACK_SIZE: usize = 256 * 1024 * 1024;
+const INTERPRETER_STACK_BUDGET: usize = INTERPRETER_STACK_SIZE - 16 * 1024 * 1024;
 
 fn main()
 {
@@ -11,12 +15,37 @@ fn main()
         eprintln!("Usage: {} <source_file>", args[0]);
         return;
     }
-    let source_file = &args[1];
+    let source_file = args[1].clone();
+    thread::Builder::new()
+        .stack_size(INTERPRETER_STACK_SIZE)
+        .spawn(move || run(&source_file))
+        .expect("Failed to start the interpreter thread")
+        .join()
+        .expect("Interpreter thread panicked");
+}
+
+fn run(source_file: &str)
+{
     let src = std::fs::read_to_string(source_file).expect("Failed to read source file");
-    let mut lexer = lexer::Lexer::new(src);
-    let tokens = lexer.tokenize();
-    let mut parser = parser::Parser::new(Some(tokens));
-    let ast = parser.parse();
+    let lexer = lexer::Lexer::new(src);
+    let mut parser = parser::Parser::new(lexer);
+    let ast = match parser.parse() 
+    {
+        Ok(ast) => ast,
+        Err(errors) => 
+        {
+            for error in errors 
+            {
+                eprintln!("{}: {}", source_file, error);
+            }
+            std::process::exit(1);
+        }
+    };
     let mut interpreter = interpreter::Interpreter::new(ast);
-    interpreter.interpret(); 
+    interpreter.stack_budget = INTERPRETER_STACK_BUDGET;
+    if let Err(error) = interpreter.interpret() 
+    {
+        eprintln!("{}: {}", source_file, error);
+        std::process::exit(1);
+    }
 }
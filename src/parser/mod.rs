@@ -1,15 +1,37 @@
-use crate::lexer::{self, Token, TypeOfToken};
+use crate::lexer::{LexError, LexErrorKind, Span, Token, TypeOfToken};
+use std::collections::VecDeque;
+use std::fmt;
+use std::rc::Rc;
 
 #[derive(Clone)]
 pub enum ASTNode
 {
     Number(NumberNode),
     String(StringNode),
+    Interpolation(InterpolationNode),
+    Bool(BoolNode),
     BinaryOp(BinaryOpNode),
     UnaryOp(UnaryOpNode),
     Variable(VariableNode),
     VariableCall(VariableCallNode),
-    Print(PrintNode)
+    Print(PrintNode),
+    Block(BlockNode),
+    If(IfNode),
+    While(WhileNode),
+    For(ForNode),
+    Foreach(ForeachNode),
+    Range(RangeNode),
+    Break(JumpNode),
+    Continue(JumpNode),
+    Function(Rc<FunctionNode>),
+    Call(CallNode),
+    Return(ReturnNode),
+    Once(OnceNode),
+    Assign(AssignNode),
+    Update(UpdateNode),
+    List(ListNode),
+    Map(MapNode),
+    Index(IndexNode)
 }
 
 impl ASTNode 
@@ -22,9 +44,61 @@ impl ASTNode
             ASTNode::BinaryOp(node) => node.display(),
             ASTNode::UnaryOp(node) => node.display(),
             ASTNode::String(node) => node.display().to_string(),
+            ASTNode::Interpolation(node) => node.display(),
+            ASTNode::Bool(node) => node.display().to_string(),
             ASTNode::Variable(node) => node.display(),
             ASTNode::VariableCall(node) => node.display(),
-            ASTNode::Print(node) => node.display()
+            ASTNode::Print(node) => node.display(),
+            ASTNode::Block(node) => node.display(),
+            ASTNode::If(node) => node.display(),
+            ASTNode::While(node) => node.display(),
+            ASTNode::For(node) => node.display(),
+            ASTNode::Foreach(node) => node.display(),
+            ASTNode::Range(node) => node.display(),
+            ASTNode::Break(node) => node.display("break"),
+            ASTNode::Continue(node) => node.display("continue"),
+            ASTNode::Function(node) => node.display(),
+            ASTNode::Call(node) => node.display(),
+            ASTNode::Return(node) => node.display(),
+            ASTNode::Once(node) => node.display(),
+            ASTNode::Assign(node) => node.display(),
+            ASTNode::Update(node) => node.display(),
+            ASTNode::List(node) => node.display(),
+            ASTNode::Map(node) => node.display(),
+            ASTNode::Index(node) => node.display()
+        }
+    }
+
+    pub fn span(&self) -> Span
+    {
+        match self 
+        {
+            ASTNode::Number(node) => node.token.span,
+            ASTNode::BinaryOp(node) => node.span,
+            ASTNode::UnaryOp(node) => node.span,
+            ASTNode::String(node) => node.token.span,
+            ASTNode::Interpolation(node) => node.span,
+            ASTNode::Bool(node) => node.token.span,
+            ASTNode::Variable(node) => node.span,
+            ASTNode::VariableCall(node) => node.span,
+            ASTNode::Print(node) => node.span,
+            ASTNode::Block(node) => node.span,
+            ASTNode::If(node) => node.span,
+            ASTNode::While(node) => node.span,
+            ASTNode::For(node) => node.span,
+            ASTNode::Foreach(node) => node.span,
+            ASTNode::Range(node) => node.span,
+            ASTNode::Break(node) => node.span,
+            ASTNode::Continue(node) => node.span,
+            ASTNode::Function(node) => node.span,
+            ASTNode::Call(node) => node.span,
+            ASTNode::Return(node) => node.span,
+            ASTNode::Once(node) => node.span,
+            ASTNode::Assign(node) => node.span,
+            ASTNode::Update(node) => node.span,
+            ASTNode::List(node) => node.span,
+            ASTNode::Map(node) => node.span,
+            ASTNode::Index(node) => node.span
         }
     }
 }
@@ -44,7 +118,7 @@ impl NumberNode
         NumberNode 
         { 
             token: token.clone(), 
-            is_neg: is_neg, 
+            is_neg, 
             value: token.value 
         }
     }
@@ -79,12 +153,64 @@ impl StringNode
     }
 }
 
+#[derive(Clone)]
+pub struct BoolNode
+{
+    pub token: Token,
+    pub value: bool,
+}
+
+impl BoolNode
+{
+    pub fn new(token: Token) -> BoolNode
+    {
+        BoolNode 
+        { 
+            value: token.value == "true", 
+            token 
+        }
+    }
+
+    pub fn display(&self) -> &str
+    {
+        &self.token.value
+    }
+}
+
+#[derive(Clone)]
+pub struct InterpolationNode
+{
+    pub parts: Vec<ASTNode>,
+    pub span: Span,
+}
+
+impl InterpolationNode
+{
+    pub fn new(parts: Vec<ASTNode>, span: Span) -> InterpolationNode
+    {
+        InterpolationNode { parts, span }
+    }
+
+    pub fn display(&self) -> String
+    {
+        let parts: Vec<String> = self.parts.iter()
+            .map(|part| match part 
+            {
+                ASTNode::String(node) => node.display().to_string(),
+                _ => format!("{{{}}}", part.display()),
+            })
+            .collect();
+        format!("\"{}\"", parts.join(""))
+    }
+}
+
 #[derive(Clone)]
 pub struct BinaryOpNode
 {
     pub left: Box<ASTNode>,
     pub right: Box<ASTNode>,
     pub operator: Token,
+    pub span: Span,
 }
 
 impl BinaryOpNode
@@ -93,6 +219,7 @@ impl BinaryOpNode
     {
         BinaryOpNode 
         { 
+            span: left.span().merge(right.span()),
             left: Box::new(left), 
             right: Box::new(right), 
             operator: operator.clone() 
@@ -110,6 +237,7 @@ pub struct UnaryOpNode
 {
     pub node: Box<ASTNode>,
     pub operator: Token,
+    pub span: Span,
 }
 
 impl UnaryOpNode
@@ -118,6 +246,7 @@ impl UnaryOpNode
     {
         UnaryOpNode 
         { 
+            span: operator.span.merge(node.span()),
             node: Box::new(node), 
             operator: operator.clone() 
         }
@@ -125,6 +254,10 @@ impl UnaryOpNode
 
     pub fn display(&self) -> String
     {
+        if self.operator.tot == TypeOfToken::KEYWORD 
+        {
+            return format!("{} {}", self.operator.value, self.node.display());
+        }
         format!("{}{}", self.operator.value, self.node.display())
     }
 }
@@ -134,13 +267,15 @@ pub struct VariableNode
 {
     pub name: String,
     pub value: Box<ASTNode>,
+    pub doc: Option<String>,
+    pub span: Span,
 }
 
 impl VariableNode
 {
-    pub fn new(name: String, value: Box<ASTNode>) -> VariableNode
+    pub fn new(name: String, value: Box<ASTNode>, doc: Option<String>, span: Span) -> VariableNode
     {
-        VariableNode { name, value }
+        VariableNode { name, value, doc, span }
     }
 
     pub fn display(&self) -> String
@@ -153,13 +288,14 @@ impl VariableNode
 pub struct VariableCallNode
 {
     pub name: String,
+    pub span: Span,
 }
 
 impl VariableCallNode
 {
-    pub fn new(name: String) -> VariableCallNode
+    pub fn new(name: String, span: Span) -> VariableCallNode
     {
-        VariableCallNode { name }
+        VariableCallNode { name, span }
     }
 
     pub fn display(&self) -> String
@@ -172,13 +308,14 @@ impl VariableCallNode
 pub struct PrintNode
 {
     pub node: Box<ASTNode>,
+    pub span: Span,
 }
 
 impl PrintNode
 {
-    pub fn new(node: ASTNode) -> PrintNode
+    pub fn new(node: ASTNode, span: Span) -> PrintNode
     {
-        PrintNode { node: Box::new(node) }
+        PrintNode { node: Box::new(node), span }
     }
 
     pub fn display(&self) -> String
@@ -187,186 +324,1442 @@ impl PrintNode
     }
 }
 
-pub struct Parser 
+#[derive(Clone)]
+pub struct BlockNode
 {
-    pub tokens: Option<Vec<Token>>,
-    pub index: usize,
-    pub column: usize,
-    pub line: usize,
+    pub statements: Vec<ASTNode>,
+    pub span: Span,
 }
 
-impl Parser 
+impl BlockNode
 {
-    pub fn new(tokens: Option<Vec<Token>>) -> Self 
+    pub fn new(statements: Vec<ASTNode>, span: Span) -> BlockNode
     {
-        Parser 
+        BlockNode { statements, span }
+    }
+
+    pub fn display(&self) -> String
+    {
+        let statements: Vec<String> = self.statements.iter().map(|statement| statement.display()).collect();
+        format!("{{ {} }}", statements.join("; "))
+    }
+}
+
+#[derive(Clone)]
+pub struct IfNode
+{
+    pub branches: Vec<(ASTNode, ASTNode)>,
+    pub else_branch: Option<Box<ASTNode>>,
+    pub span: Span,
+}
+
+impl IfNode
+{
+    pub fn new(branches: Vec<(ASTNode, ASTNode)>, else_branch: Option<ASTNode>, span: Span) -> IfNode
+    {
+        IfNode { branches, else_branch: else_branch.map(Box::new), span }
+    }
+
+    pub fn display(&self) -> String
+    {
+        let branches: Vec<String> = self.branches.iter()
+            .map(|(condition, body)| format!("if({}) {}", condition.display(), body.display()))
+            .collect();
+        let mut display = branches.join(" else");
+        if let Some(else_branch) = &self.else_branch 
         {
-            tokens,
-            index: 0,
-            column: 1,
-            line: 1,
+            display.push_str(&format!(" else {}", else_branch.display()));
         }
+        display
     }
+}
 
-    pub fn next_token(&mut self) -> Token 
+#[derive(Clone)]
+pub struct WhileNode
+{
+    pub condition: Box<ASTNode>,
+    pub body: Box<ASTNode>,
+    pub label: Option<String>,
+    pub span: Span,
+}
+
+impl WhileNode
+{
+    pub fn new(condition: ASTNode, body: ASTNode, label: Option<String>, span: Span) -> WhileNode
     {
-        if let Some(tokens) = self.tokens.clone() 
-        {
-            if self.index < tokens.len() 
-            {
-                let token = tokens[self.index].clone();
-                self.index += 1;
+        WhileNode { condition: Box::new(condition), body: Box::new(body), label, span }
+    }
 
-                if token.tot == lexer::TypeOfToken::NEWLINE 
-                {
-                    self.column = 1;
-                    self.line += 1;
-                }
-                else 
-                {
-                    self.column += token.value.len();
-                }
+    pub fn display(&self) -> String
+    {
+        let label = self.label.as_ref().map(|label| format!("{}: ", label)).unwrap_or_default();
+        format!("{}while({}) {}", label, self.condition.display(), self.body.display())
+    }
+}
 
-                return token;
-            }
-            Token::new(lexer::TypeOfToken::EOF, "".to_string())
-        }
-        else 
+#[derive(Clone)]
+pub struct ForNode
+{
+    pub variable: String,
+    pub iterable: Box<ASTNode>,
+    pub body: Box<ASTNode>,
+    pub label: Option<String>,
+    pub span: Span,
+}
+
+impl ForNode
+{
+    pub fn new(variable: String, iterable: ASTNode, body: ASTNode, label: Option<String>, span: Span) -> ForNode
+    {
+        ForNode { variable, iterable: Box::new(iterable), body: Box::new(body), label, span }
+    }
+
+    pub fn display(&self) -> String
+    {
+        let label = self.label.as_ref().map(|label| format!("{}: ", label)).unwrap_or_default();
+        format!("{}for({} in {}) {}", label, self.variable, self.iterable.display(), self.body.display())
+    }
+}
+
+#[derive(Clone)]
+pub enum Pattern
+{
+    Name(String),
+    Tuple(Vec<String>),
+}
+
+impl Pattern
+{
+    pub fn display(&self) -> String
+    {
+        match self 
         {
-            panic!("No tokens available");
+            Pattern::Name(name) => name.clone(),
+            Pattern::Tuple(names) => format!("({})", names.join(", ")),
         }
     }
+}
+
+#[derive(Clone)]
+pub struct ForeachNode
+{
+    pub pattern: Pattern,
+    pub iterable: Box<ASTNode>,
+    pub body: Box<ASTNode>,
+    pub label: Option<String>,
+    pub span: Span,
+}
 
-    pub fn peek_next_token(&self) -> &Token 
+impl ForeachNode
+{
+    pub fn new(pattern: Pattern, iterable: ASTNode, body: ASTNode, label: Option<String>, span: Span) -> ForeachNode
     {
-        if let Some(tokens) = &self.tokens 
-        {
-            if self.index < tokens.len() 
-            {
-                &tokens[self.index]
-            }
-            else if !tokens.is_empty() 
-            {
-                &tokens[tokens.len() - 1]
-            }
-            else 
-            {
-                panic!("No tokens available")
-            }
+        ForeachNode { pattern, iterable: Box::new(iterable), body: Box::new(body), label, span }
+    }
+
+    pub fn display(&self) -> String
+    {
+        let label = self.label.as_ref().map(|label| format!("{}: ", label)).unwrap_or_default();
+        format!("{}foreach({} in {}) {}", label, self.pattern.display(), self.iterable.display(), self.body.display())
+    }
+}
+
+#[derive(Clone)]
+pub struct RangeNode
+{
+    pub start: Box<ASTNode>,
+    pub end: Box<ASTNode>,
+    pub step: Option<Box<ASTNode>>,
+    pub inclusive: bool,
+    pub operator: Token,
+    pub span: Span,
+}
+
+impl RangeNode
+{
+    pub fn new(start: ASTNode, end: ASTNode, step: Option<ASTNode>, operator: Token) -> RangeNode
+    {
+        let span = start.span().merge(step.as_ref().unwrap_or(&end).span());
+        RangeNode 
+        { 
+            start: Box::new(start), 
+            end: Box::new(end), 
+            step: step.map(Box::new), 
+            inclusive: operator.value == "..=", 
+            operator, 
+            span 
         }
-        else 
+    }
+
+    pub fn display(&self) -> String
+    {
+        let mut display = format!("({}{}{})", self.start.display(), self.operator.value, self.end.display());
+        if let Some(step) = &self.step 
         {
-            panic!("No tokens available")
+            display = format!("({} step {})", display, step.display());
         }
+        display
+    }
+}
+
+#[derive(Clone)]
+pub struct JumpNode
+{
+    pub label: Option<String>,
+    pub span: Span,
+}
+
+impl JumpNode
+{
+    pub fn new(label: Option<String>, span: Span) -> JumpNode
+    {
+        JumpNode { label, span }
     }
 
-    pub fn is_at_end(&self) -> bool 
+    pub fn display(&self, keyword: &str) -> String
     {
-        if let Some(tokens) = &self.tokens 
+        match &self.label 
         {
-            self.index >= tokens.len()
+            Some(label) => format!("{} {}", keyword, label),
+            None => keyword.to_string(),
         }
-        else 
+    }
+}
+
+#[derive(Clone)]
+pub struct FunctionNode
+{
+    pub name: Option<String>,
+    pub parameters: Vec<String>,
+    pub body: Box<ASTNode>,
+    pub is_void: bool,
+    pub doc: Option<String>,
+    pub span: Span,
+}
+
+impl FunctionNode
+{
+    pub fn new(name: Option<String>, parameters: Vec<String>, body: ASTNode, is_void: bool, doc: Option<String>, span: Span) -> FunctionNode
+    {
+        FunctionNode { name, parameters, body: Box::new(body), is_void, doc, span }
+    }
+
+    pub fn title(&self) -> String
+    {
+        match &self.name 
         {
-            true
+            Some(name) => format!("function '{}'", name),
+            None => "anonymous function".to_string(),
         }
     }
 
-    pub fn parse(&mut self) -> Vec<ASTNode> 
+    pub fn display(&self) -> String
     {
-        let mut nodes = Vec::new();
+        let void = if self.is_void { "void " } else { "" };
+        let name = self.name.as_deref().unwrap_or("");
+        format!("define {}{}({}) {}", void, name, self.parameters.join(", "), self.body.display())
+    }
+}
+
+#[derive(Clone)]
+pub struct CallNode
+{
+    pub callee: Box<ASTNode>,
+    pub arguments: Vec<ASTNode>,
+    pub span: Span,
+}
+
+impl CallNode
+{
+    pub fn new(callee: ASTNode, arguments: Vec<ASTNode>, span: Span) -> CallNode
+    {
+        CallNode { callee: Box::new(callee), arguments, span }
+    }
+
+    pub fn display(&self) -> String
+    {
+        let arguments: Vec<String> = self.arguments.iter().map(|argument| argument.display()).collect();
+        format!("{}({})", self.callee.display(), arguments.join(", "))
+    }
+}
+
+#[derive(Clone)]
+pub struct ReturnNode
+{
+    pub value: Option<Box<ASTNode>>,
+    pub span: Span,
+}
+
+impl ReturnNode
+{
+    pub fn new(value: Option<ASTNode>, span: Span) -> ReturnNode
+    {
+        ReturnNode { value: value.map(Box::new), span }
+    }
 
-        while !self.is_at_end() 
+    pub fn display(&self) -> String
+    {
+        match &self.value 
         {
-            let node = self.parse_expr();
-            nodes.push(node);
+            Some(value) => format!("return {}", value.display()),
+            None => "return".to_string(),
         }
+    }
+}
 
-        nodes
+#[derive(Clone)]
+pub struct OnceNode
+{
+    pub id: usize,
+    pub body: Box<ASTNode>,
+    pub span: Span,
+}
+
+impl OnceNode
+{
+    pub fn new(id: usize, body: ASTNode, span: Span) -> OnceNode
+    {
+        OnceNode { id, body: Box::new(body), span }
     }
 
-    pub fn parse_expr(&mut self) -> ASTNode 
+    pub fn display(&self) -> String
     {
-        let mut node: ASTNode = self.parse_term();
-        while self.peek_next_token().tot == TypeOfToken::OPERATOR
-            && (self.peek_next_token().value == "+" || self.peek_next_token().value == "-")
-        {
-            let token: Token = self.next_token();
-            node = ASTNode::BinaryOp(BinaryOpNode::new(node, self.parse_term(), token))
+        format!("once {}", self.body.display())
+    }
+}
+
+#[derive(Clone)]
+pub struct AssignNode
+{
+    pub target: Box<ASTNode>,
+    pub value: Box<ASTNode>,
+    pub operator: Token,
+    pub span: Span,
+}
+
+impl AssignNode
+{
+    pub fn new(target: ASTNode, value: ASTNode, operator: Token) -> AssignNode
+    {
+        AssignNode 
+        { 
+            span: target.span().merge(value.span()),
+            target: Box::new(target), 
+            value: Box::new(value), 
+            operator 
         }
-        node
     }
 
-    pub fn parse_term(&mut self) -> ASTNode 
+    pub fn display(&self) -> String
     {
-        let mut node = self.parse_factor();
-        while self.peek_next_token().tot == TypeOfToken::OPERATOR
-            && (self.peek_next_token().value == "*" || self.peek_next_token().value == "/")
-        {
-            let token: Token = self.next_token();
-            node = ASTNode::BinaryOp(BinaryOpNode::new(node, self.parse_factor(), token))
+        format!("({} {} {})", self.target.display(), self.operator.value, self.value.display())
+    }
+}
+
+#[derive(Clone)]
+pub struct UpdateNode
+{
+    pub target: Box<ASTNode>,
+    pub operator: Token,
+    pub prefix: bool,
+    pub span: Span,
+}
+
+impl UpdateNode
+{
+    pub fn new(target: ASTNode, operator: Token, prefix: bool) -> UpdateNode
+    {
+        UpdateNode 
+        { 
+            span: target.span().merge(operator.span),
+            target: Box::new(target), 
+            operator, 
+            prefix 
         }
-        node
     }
 
-    pub fn parse_factor(&mut self) -> ASTNode 
-    { 
-        let token: Token = self.next_token();
-        match token.tot 
+    pub fn display(&self) -> String
+    {
+        if self.prefix 
         {
-            TypeOfToken::NUMBER => 
-            {
-                ASTNode::Number(NumberNode::new(token, false))
-            }
-            TypeOfToken::OPERATOR if token.value == "-" => 
-            {
-                let node = self.parse_factor();
-                ASTNode::UnaryOp(UnaryOpNode::new(node, token))
-            }
-            TypeOfToken::BLOCKDELIMITERS if token.value == "(" => 
-            {
-                let node = self.parse_expr();
-                self.expect_token(TypeOfToken::BLOCKDELIMITERS, ")");
-                node
-            }
-            TypeOfToken::IDENTIFIER =>
-            {
-                ASTNode::VariableCall(VariableCallNode::new(token.value))
-            }
-            TypeOfToken::KEYWORD => 
-            {
-                let keyword = token.value.clone();
-                match keyword.as_str() 
-                {
-                    "print" => 
-                    {
-                        let node = self.parse_expr();
-                        ASTNode::Print(PrintNode::new(node))
-                    }
-                    "var" => 
-                    {
-                        let name = self.next_token();
-                        self.expect_token(TypeOfToken::OPERATOR, "=");
-                        let value = self.parse_expr();
-                        ASTNode::Variable(VariableNode::new(name.value, Box::new(value)))
-                    }
-                    _ => panic!("Unexpected keyword: {}", keyword),
-                }
-            }
-            TypeOfToken::STRING => 
-            {
-                ASTNode::String(StringNode::new(token))
-            }
-            _ => panic!("Unexpected token: {}", token.to_string()),
+            format!("({}{})", self.operator.value, self.target.display())
+        }
+        else 
+        {
+            format!("({}{})", self.target.display(), self.operator.value)
         }
     }
+}
 
-    fn expect_token(&mut self, expected_type: TypeOfToken, expected_value: &str) 
+#[derive(Clone)]
+pub struct ListNode
+{
+    pub elements: Vec<ASTNode>,
+    pub span: Span,
+}
+
+impl ListNode
+{
+    pub fn new(elements: Vec<ASTNode>, span: Span) -> ListNode
     {
-        let token = self.next_token();
+        ListNode { elements, span }
+    }
+
+    pub fn display(&self) -> String
+    {
+        let elements: Vec<String> = self.elements.iter().map(|element| element.display()).collect();
+        format!("[{}]", elements.join(", "))
+    }
+}
+
+#[derive(Clone)]
+pub struct MapNode
+{
+    pub entries: Vec<(ASTNode, ASTNode)>,
+    pub span: Span,
+}
+
+impl MapNode
+{
+    pub fn new(entries: Vec<(ASTNode, ASTNode)>, span: Span) -> MapNode
+    {
+        MapNode { entries, span }
+    }
+
+    pub fn display(&self) -> String
+    {
+        let entries: Vec<String> = self.entries.iter().map(|(key, value)| format!("{}: {}", key.display(), value.display())).collect();
+        format!("{{{}}}", entries.join(", "))
+    }
+}
+
+#[derive(Clone)]
+pub struct IndexNode
+{
+    pub target: Box<ASTNode>,
+    pub index: Box<ASTNode>,
+    pub span: Span,
+}
+
+impl IndexNode
+{
+    pub fn new(target: ASTNode, index: ASTNode, span: Span) -> IndexNode
+    {
+        IndexNode { target: Box::new(target), index: Box::new(index), span }
+    }
+
+    pub fn display(&self) -> String
+    {
+        format!("{}[{}]", self.target.display(), self.index.display())
+    }
+}
+
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub enum Fixity
+{
+    Prefix,
+    Infix,
+    Postfix,
+}
+
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub enum Associativity
+{
+    Left,
+    Right,
+}
+
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub enum OperatorForm
+{
+    Unary,
+    Binary,
+    Update,
+    Assignment,
+    Range,
+}
+
+pub struct OperatorInfo
+{
+    pub operator: &'static str,
+    pub fixity: Fixity,
+    pub form: OperatorForm,
+    pub precedence: u8,
+    pub associativity: Associativity,
+}
+
+impl OperatorInfo
+{
+    pub fn lookup(token: &Token, fixity: Fixity) -> Option<&'static OperatorInfo>
+    {
+        if token.tot != TypeOfToken::OPERATOR && token.tot != TypeOfToken::KEYWORD 
+        {
+            return None;
+        }
+        OPERATOR_TABLE.iter().find(|info| info.fixity == fixity && info.operator == token.value)
+    }
+
+    pub fn left_binding_power(&self) -> u8
+    {
+        match self.associativity 
+        {
+            Associativity::Left => self.precedence * 2,
+            Associativity::Right => self.precedence * 2 + 1,
+        }
+    }
+
+    pub fn right_binding_power(&self) -> u8
+    {
+        match self.associativity 
+        {
+            Associativity::Left => self.precedence * 2 + 1,
+            Associativity::Right => self.precedence * 2,
+        }
+    }
+}
+
+const MAX_DEPTH: usize = 128;
+
+pub static OPERATOR_TABLE: &[OperatorInfo] =
+&[
+    OperatorInfo { operator: "=", fixity: Fixity::Infix, form: OperatorForm::Assignment, precedence: 1, associativity: Associativity::Right },
+    OperatorInfo { operator: "+=", fixity: Fixity::Infix, form: OperatorForm::Assignment, precedence: 1, associativity: Associativity::Right },
+    OperatorInfo { operator: "-=", fixity: Fixity::Infix, form: OperatorForm::Assignment, precedence: 1, associativity: Associativity::Right },
+    OperatorInfo { operator: "*=", fixity: Fixity::Infix, form: OperatorForm::Assignment, precedence: 1, associativity: Associativity::Right },
+    OperatorInfo { operator: "/=", fixity: Fixity::Infix, form: OperatorForm::Assignment, precedence: 1, associativity: Associativity::Right },
+    OperatorInfo { operator: "or", fixity: Fixity::Infix, form: OperatorForm::Binary, precedence: 3, associativity: Associativity::Left },
+    OperatorInfo { operator: "and", fixity: Fixity::Infix, form: OperatorForm::Binary, precedence: 4, associativity: Associativity::Left },
+    OperatorInfo { operator: "not", fixity: Fixity::Prefix, form: OperatorForm::Unary, precedence: 5, associativity: Associativity::Right },
+    OperatorInfo { operator: "==", fixity: Fixity::Infix, form: OperatorForm::Binary, precedence: 6, associativity: Associativity::Left },
+    OperatorInfo { operator: "!=", fixity: Fixity::Infix, form: OperatorForm::Binary, precedence: 6, associativity: Associativity::Left },
+    OperatorInfo { operator: "<", fixity: Fixity::Infix, form: OperatorForm::Binary, precedence: 7, associativity: Associativity::Left },
+    OperatorInfo { operator: ">", fixity: Fixity::Infix, form: OperatorForm::Binary, precedence: 7, associativity: Associativity::Left },
+    OperatorInfo { operator: "<=", fixity: Fixity::Infix, form: OperatorForm::Binary, precedence: 7, associativity: Associativity::Left },
+    OperatorInfo { operator: ">=", fixity: Fixity::Infix, form: OperatorForm::Binary, precedence: 7, associativity: Associativity::Left },
+    OperatorInfo { operator: "..", fixity: Fixity::Infix, form: OperatorForm::Range, precedence: 8, associativity: Associativity::Left },
+    OperatorInfo { operator: "..=", fixity: Fixity::Infix, form: OperatorForm::Range, precedence: 8, associativity: Associativity::Left },
+    OperatorInfo { operator: "+", fixity: Fixity::Infix, form: OperatorForm::Binary, precedence: 10, associativity: Associativity::Left },
+    OperatorInfo { operator: "-", fixity: Fixity::Infix, form: OperatorForm::Binary, precedence: 10, associativity: Associativity::Left },
+    OperatorInfo { operator: "*", fixity: Fixity::Infix, form: OperatorForm::Binary, precedence: 20, associativity: Associativity::Left },
+    OperatorInfo { operator: "/", fixity: Fixity::Infix, form: OperatorForm::Binary, precedence: 20, associativity: Associativity::Left },
+    OperatorInfo { operator: "-", fixity: Fixity::Prefix, form: OperatorForm::Unary, precedence: 25, associativity: Associativity::Right },
+    OperatorInfo { operator: "++", fixity: Fixity::Prefix, form: OperatorForm::Update, precedence: 25, associativity: Associativity::Right },
+    OperatorInfo { operator: "--", fixity: Fixity::Prefix, form: OperatorForm::Update, precedence: 25, associativity: Associativity::Right },
+    OperatorInfo { operator: "**", fixity: Fixity::Infix, form: OperatorForm::Binary, precedence: 30, associativity: Associativity::Right },
+    OperatorInfo { operator: "++", fixity: Fixity::Postfix, form: OperatorForm::Update, precedence: 35, associativity: Associativity::Left },
+    OperatorInfo { operator: "--", fixity: Fixity::Postfix, form: OperatorForm::Update, precedence: 35, associativity: Associativity::Left },
+];
+
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone)]
+pub enum ParseErrorKind
+{
+    UnexpectedToken { expected: String, found: String },
+    UnexpectedEndOfInput { expected: String },
+    OutsideLoop(String),
+    UnknownLabel(String),
+    OutsideFunction,
+    DuplicateParameter(String),
+    VoidReturnsValue,
+    MissingReturnValue,
+    InvalidAssignmentTarget,
+    TooDeeplyNested,
+    Lex(LexErrorKind),
+}
+
+impl fmt::Display for ParseErrorKind
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        match self 
+        {
+            ParseErrorKind::UnexpectedToken { expected, found } => write!(f, "Expected {}, but found {}", expected, found),
+            ParseErrorKind::UnexpectedEndOfInput { expected } => write!(f, "Expected {}, but reached the end of input", expected),
+            ParseErrorKind::OutsideLoop(keyword) => write!(f, "'{}' used outside of a loop", keyword),
+            ParseErrorKind::UnknownLabel(label) => write!(f, "Unknown loop label '{}'", label),
+            ParseErrorKind::OutsideFunction => write!(f, "'return' used outside of a function"),
+            ParseErrorKind::DuplicateParameter(name) => write!(f, "Duplicate parameter '{}'", name),
+            ParseErrorKind::VoidReturnsValue => write!(f, "A void function cannot return a value"),
+            ParseErrorKind::MissingReturnValue => write!(f, "A function that is not void must return a value"),
+            ParseErrorKind::InvalidAssignmentTarget => write!(f, "Invalid assignment target"),
+            ParseErrorKind::TooDeeplyNested => write!(f, "Nesting deeper than {} levels", MAX_DEPTH),
+            ParseErrorKind::Lex(kind) => write!(f, "{}", kind),
+        }
+    }
+}
+
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct ParseError
+{
+    pub kind: ParseErrorKind,
+    pub span: Span,
+}
+
+impl ParseError
+{
+    pub fn new(kind: ParseErrorKind, span: Span) -> ParseError
+    {
+        ParseError { kind, span }
+    }
+
+    pub fn unexpected(token: &Token, expected: &str) -> ParseError
+    {
+        let kind = match token.tot 
+        {
+            TypeOfToken::EOF => ParseErrorKind::UnexpectedEndOfInput { expected: expected.to_string() },
+            TypeOfToken::NEWLINE => ParseErrorKind::UnexpectedToken { expected: expected.to_string(), found: "newline".to_string() },
+            _ => ParseErrorKind::UnexpectedToken { expected: expected.to_string(), found: format!("{} '{}'", token.tot, token.value) },
+        };
+        ParseError::new(kind, token.span)
+    }
+}
+
+impl From<LexError> for ParseError
+{
+    fn from(error: LexError) -> ParseError
+    {
+        ParseError::new(ParseErrorKind::Lex(error.kind), error.span)
+    }
+}
+
+impl fmt::Display for ParseError
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        write!(f, "{} at {}", self.kind, self.span)
+    }
+}
+
+pub struct Parser 
+{
+    tokens: Box<dyn Iterator<Item = Result<Token, LexError>>>,
+    lookahead: VecDeque<Token>,
+    last_span: Span,
+    doc: Option<String>,
+    errors: Vec<ParseError>,
+    nesting: usize,
+    depth: usize,
+    blocks: usize,
+    loops: Vec<Option<String>>,
+    functions: Vec<bool>,
+    once_count: usize,
+}
+
+impl Parser 
+{
+    pub fn new(tokens: impl Iterator<Item = Result<Token, LexError>> + 'static) -> Self 
+    {
+        Parser 
+        {
+            tokens: Box::new(tokens),
+            lookahead: VecDeque::new(),
+            last_span: Span::new(1, 1, 0, 0, 1, 1),
+            doc: None,
+            errors: Vec::new(),
+            nesting: 0,
+            depth: 0,
+            blocks: 0,
+            loops: Vec::new(),
+            functions: Vec::new(),
+            once_count: 0,
+        }
+    }
+
+    pub fn from_tokens(tokens: Vec<Token>) -> Self 
+    {
+        Parser::new(tokens.into_iter().map(Ok))
+    }
+
+    fn fill_lookahead(&mut self, n: usize)
+    {
+        while self.lookahead.len() <= n 
+        {
+            if self.lookahead.back().is_some_and(|token| token.tot == TypeOfToken::EOF) 
+            {
+                return;
+            }
+            let token = match self.tokens.next() 
+            {
+                Some(Ok(token)) => token,
+                Some(Err(error)) => 
+                {
+                    let token = Token::new(TypeOfToken::ERROR, error.kind.to_string(), error.span);
+                    self.errors.push(ParseError::from(error));
+                    token
+                }
+                None => 
+                {
+                    let (line, column, end) = (self.last_span.end_line, self.last_span.end_column, self.last_span.end);
+                    let span = Span::new(line, column, end, end, line, column);
+                    Token::new(TypeOfToken::EOF, "".to_string(), span)
+                }
+            };
+            self.last_span = token.span;
+            self.lookahead.push_back(token);
+        }
+    }
+
+    pub fn next_token(&mut self) -> Token 
+    {
+        self.fill_lookahead(0);
+        if self.lookahead.len() == 1 && self.lookahead[0].tot == TypeOfToken::EOF 
+        {
+            return self.lookahead[0].clone();
+        }
+        self.lookahead.pop_front().unwrap()
+    }
+
+    pub fn peek_nth_token(&mut self, n: usize) -> &Token 
+    {
+        self.fill_lookahead(n);
+        let last = self.lookahead.len() - 1;
+        &self.lookahead[n.min(last)]
+    }
+
+    pub fn peek_next_token(&mut self) -> &Token 
+    {
+        self.peek_nth_token(0)
+    }
+
+    pub fn is_at_end(&mut self) -> bool 
+    {
+        self.peek_next_token().tot == TypeOfToken::EOF
+    }
+
+    fn is_terminator(token: &Token) -> bool
+    {
+        token.tot == TypeOfToken::NEWLINE 
+            || token.tot == TypeOfToken::DOCCOMMENT 
+            || token.tot == TypeOfToken::EOF 
+            || (token.tot == TypeOfToken::PUNCTUATION && token.value == ";")
+    }
+
+    fn is_line_break(token: &Token) -> bool
+    {
+        token.tot == TypeOfToken::NEWLINE || token.tot == TypeOfToken::DOCCOMMENT
+    }
+
+    fn skip_newlines(&mut self)
+    {
+        while Parser::is_line_break(self.peek_next_token()) 
+        {
+            self.next_token();
+        }
+    }
+
+    fn skip_nested_newlines(&mut self)
+    {
+        if self.nesting > 0 
+        {
+            self.skip_newlines();
+        }
+    }
+
+    fn skip_terminators(&mut self)
+    {
+        while !self.is_at_end() && Parser::is_terminator(self.peek_next_token()) 
+        {
+            let token = self.next_token();
+            if token.tot == TypeOfToken::DOCCOMMENT 
+            {
+                match &mut self.doc 
+                {
+                    Some(doc) => 
+                    {
+                        doc.push('\n');
+                        doc.push_str(&token.value);
+                    }
+                    None => self.doc = Some(token.value),
+                }
+            }
+        }
+    }
+
+    fn is_block_end(token: &Token) -> bool
+    {
+        token.tot == TypeOfToken::BLOCKDELIMITERS && token.value == "}"
+    }
+
+    fn peek_past_newlines(&mut self) -> &Token 
+    {
+        let mut n = 0;
+        while Parser::is_line_break(self.peek_nth_token(n)) 
+        {
+            n += 1;
+        }
+        self.peek_nth_token(n)
+    }
+
+    fn synchronize(&mut self)
+    {
+        self.nesting = 0;
+        let mut depth: usize = 0;
+        loop 
+        {
+            let token = self.peek_next_token();
+            if token.tot == TypeOfToken::EOF || (depth == 0 && Parser::is_terminator(token)) 
+            {
+                break;
+            }
+            if token.tot == TypeOfToken::BLOCKDELIMITERS && token.value == "{" 
+            {
+                depth += 1;
+            }
+            else if Parser::is_block_end(token) 
+            {
+                if depth == 0 && self.blocks > 0 
+                {
+                    break;
+                }
+                depth = depth.saturating_sub(1);
+            }
+            self.next_token();
+        }
+    }
+
+    fn report(&mut self, error: ParseError)
+    {
+        let follows_lex_error = self.errors.iter()
+            .any(|reported| matches!(reported.kind, ParseErrorKind::Lex(_)) && reported.span == error.span);
+        if !follows_lex_error 
+        {
+            self.errors.push(error);
+        }
+    }
+
+    fn at_statements_end(&mut self) -> bool
+    {
+        self.is_at_end() || (self.blocks > 0 && Parser::is_block_end(self.peek_next_token()))
+    }
+
+    fn parse_statements(&mut self) -> Vec<ASTNode>
+    {
+        let mut nodes = Vec::new();
+
+        self.skip_terminators();
+        while !self.at_statements_end() 
+        {
+            match self.parse_statement() 
+            {
+                Ok(node) => 
+                {
+                    nodes.push(node);
+                    if !Parser::is_terminator(self.peek_next_token()) && !self.at_statements_end() 
+                    {
+                        let error = ParseError::unexpected(self.peek_next_token(), "end of statement");
+                        self.report(error);
+                        self.synchronize();
+                    }
+                }
+                Err(error) => 
+                {
+                    self.report(error);
+                    self.synchronize();
+                }
+            }
+            self.skip_terminators();
+        }
+
+        nodes
+    }
+
+    pub fn parse(&mut self) -> Result<Vec<ASTNode>, Vec<ParseError>> 
+    {
+        let nodes = self.parse_statements();
+
+        if self.errors.is_empty() 
+        {
+            Ok(nodes)
+        }
+        else 
+        {
+            let mut errors = std::mem::take(&mut self.errors);
+            errors.sort_by_key(|error| error.span.start);
+            Err(errors)
+        }
+    }
+
+    pub fn parse_block(&mut self) -> Result<ASTNode, ParseError> 
+    {
+        self.skip_newlines();
+        let open = self.expect_token(TypeOfToken::BLOCKDELIMITERS, "{")?;
+        self.enter_nesting()?;
+        let nesting = std::mem::replace(&mut self.nesting, 0);
+        self.blocks += 1;
+        let statements = self.parse_statements();
+        self.blocks -= 1;
+        self.depth -= 1;
+        self.nesting = nesting;
+        let close = self.expect_token(TypeOfToken::BLOCKDELIMITERS, "}")?;
+        Ok(ASTNode::Block(BlockNode::new(statements, open.span.merge(close.span))))
+    }
+
+    fn parse_pattern(&mut self) -> Result<Pattern, ParseError> 
+    {
+        if self.peek_next_token().tot != TypeOfToken::BLOCKDELIMITERS || self.peek_next_token().value != "(" 
+        {
+            return Ok(Pattern::Name(self.expect_type(TypeOfToken::IDENTIFIER, "loop variable")?.value));
+        }
+        self.next_token();
+        let mut names = vec![self.expect_type(TypeOfToken::IDENTIFIER, "loop variable")?.value];
+        while self.peek_next_token().tot == TypeOfToken::PUNCTUATION && self.peek_next_token().value == "," 
+        {
+            self.next_token();
+            names.push(self.expect_type(TypeOfToken::IDENTIFIER, "loop variable")?.value);
+        }
+        self.expect_token(TypeOfToken::BLOCKDELIMITERS, ")")?;
+        Ok(Pattern::Tuple(names))
+    }
+
+    fn parse_loop(&mut self, label: Option<Token>) -> Result<ASTNode, ParseError> 
+    {
+        let token = self.peek_next_token().clone();
+        if token.tot != TypeOfToken::KEYWORD || !["while", "for", "foreach"].contains(&token.value.as_str()) 
+        {
+            return Err(ParseError::unexpected(&token, "a loop after the label"));
+        }
+        self.next_token();
+        let start = label.as_ref().map_or(token.span, |label| label.span);
+        let label = label.map(|label| label.value);
+        let mut pattern = None;
+        if token.value == "for" 
+        {
+            pattern = Some(Pattern::Name(self.expect_type(TypeOfToken::IDENTIFIER, "loop variable")?.value));
+            self.expect_token(TypeOfToken::KEYWORD, "in")?;
+        }
+        else if token.value == "foreach" 
+        {
+            pattern = Some(self.parse_pattern()?);
+            self.expect_token(TypeOfToken::KEYWORD, "in")?;
+        }
+        let head = self.parse_expr()?;
+        self.loops.push(label.clone());
+        let body = self.parse_block();
+        self.loops.pop();
+        let body = body?;
+        let span = start.merge(body.span());
+        match pattern 
+        {
+            Some(Pattern::Name(variable)) if token.value == "for" => Ok(ASTNode::For(ForNode::new(variable, head, body, label, span))),
+            Some(pattern) => Ok(ASTNode::Foreach(ForeachNode::new(pattern, head, body, label, span))),
+            None => Ok(ASTNode::While(WhileNode::new(head, body, label, span))),
+        }
+    }
+
+    fn parse_jump(&mut self) -> Result<ASTNode, ParseError> 
+    {
+        let token = self.next_token();
+        let mut span = token.span;
+        let mut label = None;
+        if self.peek_next_token().tot == TypeOfToken::IDENTIFIER 
+        {
+            let name = self.next_token();
+            span = span.merge(name.span);
+            if !self.loops.contains(&Some(name.value.clone())) 
+            {
+                return Err(ParseError::new(ParseErrorKind::UnknownLabel(name.value), name.span));
+            }
+            label = Some(name.value);
+        }
+        if self.loops.is_empty() 
+        {
+            return Err(ParseError::new(ParseErrorKind::OutsideLoop(token.value), token.span));
+        }
+        let node = JumpNode::new(label, span);
+        if token.value == "break" 
+        {
+            Ok(ASTNode::Break(node))
+        }
+        else 
+        {
+            Ok(ASTNode::Continue(node))
+        }
+    }
+
+    fn parse_parameters(&mut self) -> Result<Vec<String>, ParseError> 
+    {
+        self.expect_token(TypeOfToken::BLOCKDELIMITERS, "(")?;
+        self.nesting += 1;
+        self.skip_newlines();
+        let mut parameters: Vec<String> = Vec::new();
+        while self.peek_next_token().tot != TypeOfToken::BLOCKDELIMITERS || self.peek_next_token().value != ")" 
+        {
+            let name = self.expect_type(TypeOfToken::IDENTIFIER, "parameter name")?;
+            if parameters.contains(&name.value) 
+            {
+                return Err(ParseError::new(ParseErrorKind::DuplicateParameter(name.value), name.span));
+            }
+            parameters.push(name.value);
+            self.skip_newlines();
+            if self.peek_next_token().tot != TypeOfToken::PUNCTUATION || self.peek_next_token().value != "," 
+            {
+                break;
+            }
+            self.next_token();
+            self.skip_newlines();
+        }
+        self.expect_token(TypeOfToken::BLOCKDELIMITERS, ")")?;
+        self.nesting -= 1;
+        Ok(parameters)
+    }
+
+    fn is_named_define(&mut self) -> bool
+    {
+        let next = self.peek_nth_token(1);
+        let offset = if next.tot == TypeOfToken::KEYWORD && next.value == "void" { 2 } else { 1 };
+        self.peek_nth_token(offset).tot == TypeOfToken::IDENTIFIER
+    }
+
+    fn parse_define(&mut self, named: bool, doc: Option<String>) -> Result<ASTNode, ParseError> 
+    {
+        let token = self.next_token();
+        let is_void = self.peek_next_token().tot == TypeOfToken::KEYWORD && self.peek_next_token().value == "void";
+        if is_void 
+        {
+            self.next_token();
+        }
+        let mut name = None;
+        if named 
+        {
+            name = Some(self.expect_type(TypeOfToken::IDENTIFIER, "function name")?.value);
+        }
+        let parameters = self.parse_parameters()?;
+        let loops = std::mem::take(&mut self.loops);
+        self.functions.push(is_void);
+        let body = self.parse_block();
+        self.functions.pop();
+        self.loops = loops;
+        let body = body?;
+        let span = token.span.merge(body.span());
+        Ok(ASTNode::Function(Rc::new(FunctionNode::new(name, parameters, body, is_void, doc, span))))
+    }
+
+    fn parse_return(&mut self) -> Result<ASTNode, ParseError> 
+    {
+        let token = self.next_token();
+        let Some(&is_void) = self.functions.last() else 
+        {
+            return Err(ParseError::new(ParseErrorKind::OutsideFunction, token.span));
+        };
+        let next = self.peek_next_token();
+        if Parser::is_terminator(next) || Parser::is_block_end(next) 
+        {
+            if !is_void 
+            {
+                return Err(ParseError::new(ParseErrorKind::MissingReturnValue, token.span));
+            }
+            return Ok(ASTNode::Return(ReturnNode::new(None, token.span)));
+        }
+        let value = self.parse_expr()?;
+        if is_void 
+        {
+            return Err(ParseError::new(ParseErrorKind::VoidReturnsValue, value.span()));
+        }
+        let span = token.span.merge(value.span());
+        Ok(ASTNode::Return(ReturnNode::new(Some(value), span)))
+    }
+
+    fn parse_expressions(&mut self, close: &str) -> Result<(Vec<ASTNode>, Span), ParseError> 
+    {
+        self.next_token();
+        self.nesting += 1;
+        self.skip_newlines();
+        let mut expressions = Vec::new();
+        while self.peek_next_token().tot != TypeOfToken::BLOCKDELIMITERS || self.peek_next_token().value != close 
+        {
+            expressions.push(self.parse_expr()?);
+            if self.peek_next_token().tot != TypeOfToken::PUNCTUATION || self.peek_next_token().value != "," 
+            {
+                break;
+            }
+            self.next_token();
+            self.skip_newlines();
+        }
+        let close = self.expect_token(TypeOfToken::BLOCKDELIMITERS, close)?;
+        self.nesting -= 1;
+        Ok((expressions, close.span))
+    }
+
+    fn parse_map(&mut self) -> Result<ASTNode, ParseError> 
+    {
+        let open = self.next_token();
+        self.nesting += 1;
+        self.skip_newlines();
+        let mut entries = Vec::new();
+        while self.peek_next_token().tot != TypeOfToken::BLOCKDELIMITERS || self.peek_next_token().value != "}" 
+        {
+            let key = self.parse_expr()?;
+            self.expect_token(TypeOfToken::PUNCTUATION, ":")?;
+            self.skip_newlines();
+            let value = self.parse_expr()?;
+            entries.push((key, value));
+            if self.peek_next_token().tot != TypeOfToken::PUNCTUATION || self.peek_next_token().value != "," 
+            {
+                break;
+            }
+            self.next_token();
+            self.skip_newlines();
+        }
+        let close = self.expect_token(TypeOfToken::BLOCKDELIMITERS, "}")?;
+        self.nesting -= 1;
+        Ok(ASTNode::Map(MapNode::new(entries, open.span.merge(close.span))))
+    }
+
+    fn parse_var(&mut self, doc: Option<String>) -> Result<VariableNode, ParseError> 
+    {
+        let token = self.next_token();
+        let name = self.expect_type(TypeOfToken::IDENTIFIER, "variable name")?;
+        self.expect_token(TypeOfToken::OPERATOR, "=")?;
+        self.skip_newlines();
+        let value = self.parse_expr()?;
+        let span = token.span.merge(value.span());
+        Ok(VariableNode::new(name.value, Box::new(value), doc, span))
+    }
+
+    fn next_once_id(&mut self) -> usize
+    {
+        self.once_count += 1;
+        self.once_count
+    }
+
+    fn parse_once_var(&mut self, doc: Option<String>) -> Result<ASTNode, ParseError> 
+    {
+        let token = self.next_token();
+        let mut node = self.parse_var(doc)?;
+        let value = *node.value;
+        let span = token.span.merge(value.span());
+        node.value = Box::new(ASTNode::Once(OnceNode::new(self.next_once_id(), value, span)));
+        node.span = token.span.merge(node.span);
+        Ok(ASTNode::Variable(node))
+    }
+
+    fn parse_if(&mut self) -> Result<ASTNode, ParseError> 
+    {
+        let token = self.next_token();
+        let mut branches = Vec::new();
+        let condition = self.parse_expr()?;
+        let body = self.parse_block()?;
+        let mut span = token.span.merge(body.span());
+        branches.push((condition, body));
+        let mut else_branch = None;
+        loop 
+        {
+            let next = self.peek_past_newlines();
+            if next.tot != TypeOfToken::KEYWORD || (next.value != "elseif" && next.value != "else") 
+            {
+                break;
+            }
+            let is_else = next.value == "else";
+            self.skip_newlines();
+            self.next_token();
+            if is_else 
+            {
+                let body = self.parse_block()?;
+                span = span.merge(body.span());
+                else_branch = Some(body);
+                break;
+            }
+            let condition = self.parse_expr()?;
+            let body = self.parse_block()?;
+            span = span.merge(body.span());
+            branches.push((condition, body));
+        }
+        Ok(ASTNode::If(IfNode::new(branches, else_branch, span)))
+    }
+
+    pub fn parse_statement(&mut self) -> Result<ASTNode, ParseError> 
+    {
+        let doc = self.doc.take();
+        let token = self.peek_next_token().clone();
+        if token.tot == TypeOfToken::IDENTIFIER 
+        {
+            let next = self.peek_nth_token(1);
+            if next.tot == TypeOfToken::PUNCTUATION && next.value == ":" 
+            {
+                self.next_token();
+                self.next_token();
+                return self.parse_loop(Some(token));
+            }
+        }
+        if token.tot == TypeOfToken::BLOCKDELIMITERS && token.value == "{" 
+        {
+            return self.parse_block();
+        }
+        if token.tot != TypeOfToken::KEYWORD 
+        {
+            return self.parse_expr();
+        }
+        match token.value.as_str() 
+        {
+            "print" => 
+            {
+                self.next_token();
+                let node = self.parse_expr()?;
+                let span = token.span.merge(node.span());
+                Ok(ASTNode::Print(PrintNode::new(node, span)))
+            }
+            "var" => Ok(ASTNode::Variable(self.parse_var(doc)?)),
+            "once" if self.peek_nth_token(1).tot == TypeOfToken::KEYWORD && self.peek_nth_token(1).value == "var" => self.parse_once_var(doc),
+            "if" => self.parse_if(),
+            "while" | "for" | "foreach" => self.parse_loop(None),
+            "break" | "continue" => self.parse_jump(),
+            "define" if self.is_named_define() => self.parse_define(true, doc),
+            "return" => self.parse_return(),
+            _ => self.parse_expr(),
+        }
+    }
+
+    pub fn parse_expr(&mut self) -> Result<ASTNode, ParseError> 
+    {
+        self.parse_expr_bp(0)
+    }
+
+    fn check_assignable(target: &ASTNode) -> Result<(), ParseError>
+    {
+        match target 
+        {
+            ASTNode::VariableCall(_) | ASTNode::Index(_) => Ok(()),
+            _ => Err(ParseError::new(ParseErrorKind::InvalidAssignmentTarget, target.span())),
+        }
+    }
+
+    fn enter_nesting(&mut self) -> Result<(), ParseError>
+    {
+        if self.depth >= MAX_DEPTH 
+        {
+            return Err(ParseError::new(ParseErrorKind::TooDeeplyNested, self.peek_next_token().span));
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    fn parse_expr_bp(&mut self, min_bp: u8) -> Result<ASTNode, ParseError> 
+    {
+        self.enter_nesting()?;
+        let node = self.parse_operation(min_bp);
+        self.depth -= 1;
+        node
+    }
+
+    fn parse_operation(&mut self, min_bp: u8) -> Result<ASTNode, ParseError> 
+    {
+        let node = self.parse_operand()?;
+        self.parse_operators(node, min_bp)
+    }
+
+    fn parse_operand(&mut self) -> Result<ASTNode, ParseError> 
+    {
+        let token = self.peek_next_token().clone();
+        let Some(info) = OperatorInfo::lookup(&token, Fixity::Prefix) else 
+        {
+            return self.parse_factor();
+        };
+        self.next_token();
+        let operand = self.parse_expr_bp(info.right_binding_power())?;
+        if info.form == OperatorForm::Update 
+        {
+            Parser::check_assignable(&operand)?;
+            return Ok(ASTNode::Update(UpdateNode::new(operand, token, true)));
+        }
+        Ok(ASTNode::UnaryOp(UnaryOpNode::new(operand, token)))
+    }
+
+    fn parse_operators(&mut self, mut node: ASTNode, min_bp: u8) -> Result<ASTNode, ParseError> 
+    {
+        loop 
+        {
+            self.skip_nested_newlines();
+            let token = self.peek_next_token().clone();
+            if let Some(info) = OperatorInfo::lookup(&token, Fixity::Postfix) 
+            {
+                if info.left_binding_power() < min_bp 
+                {
+                    break;
+                }
+                self.next_token();
+                Parser::check_assignable(&node)?;
+                node = ASTNode::Update(UpdateNode::new(node, token, false));
+                continue;
+            }
+            match OperatorInfo::lookup(&token, Fixity::Infix) 
+            {
+                Some(info) if info.left_binding_power() >= min_bp => node = self.parse_infix(node, token, info)?,
+                _ => break,
+            }
+        }
+        Ok(node)
+    }
+
+    fn parse_infix(&mut self, node: ASTNode, token: Token, info: &OperatorInfo) -> Result<ASTNode, ParseError> 
+    {
+        self.next_token();
+        self.skip_newlines();
+        let right = self.parse_expr_bp(info.right_binding_power())?;
+        if info.form == OperatorForm::Range 
+        {
+            let mut step = None;
+            if self.peek_next_token().tot == TypeOfToken::IDENTIFIER && self.peek_next_token().value == "step" 
+            {
+                self.next_token();
+                step = Some(self.parse_expr_bp(info.right_binding_power())?);
+            }
+            return Ok(ASTNode::Range(RangeNode::new(node, right, step, token)));
+        }
+        if info.form == OperatorForm::Assignment 
+        {
+            Parser::check_assignable(&node)?;
+            return Ok(ASTNode::Assign(AssignNode::new(node, right, token)));
+        }
+        Ok(ASTNode::BinaryOp(BinaryOpNode::new(node, right, token)))
+    }
+
+    pub fn parse_factor(&mut self) -> Result<ASTNode, ParseError> 
+    { 
+        let node = self.parse_primary()?;
+        self.parse_postfix(node)
+    }
+
+    fn parse_primary(&mut self) -> Result<ASTNode, ParseError> 
+    { 
+        let token: Token = self.peek_next_token().clone();
+        match token.tot 
+        {
+            TypeOfToken::BLOCKDELIMITERS if token.value == "(" => self.parse_group(),
+            TypeOfToken::BLOCKDELIMITERS if token.value == "[" => self.parse_list(token),
+            TypeOfToken::BLOCKDELIMITERS if token.value == "{" => self.parse_map(),
+            TypeOfToken::INTERPOLATIONSTART => 
+            {
+                self.next_token();
+                self.parse_interpolation(token)
+            }
+            TypeOfToken::KEYWORD if token.value == "define" => self.parse_define(false, None),
+            TypeOfToken::KEYWORD if token.value == "once" => self.parse_once(token),
+            _ => self.parse_literal(token),
+        }
+    }
+
+    fn parse_literal(&mut self, token: Token) -> Result<ASTNode, ParseError> 
+    { 
+        let node = match token.tot 
+        {
+            TypeOfToken::NUMBER => ASTNode::Number(NumberNode::new(token, false)),
+            TypeOfToken::IDENTIFIER => ASTNode::VariableCall(VariableCallNode::new(token.value, token.span)),
+            TypeOfToken::STRING => ASTNode::String(StringNode::new(token)),
+            TypeOfToken::KEYWORD if token.value == "true" || token.value == "false" => ASTNode::Bool(BoolNode::new(token)),
+            _ => return Err(ParseError::unexpected(&token, "an expression")),
+        };
+        self.next_token();
+        Ok(node)
+    }
+
+    fn parse_group(&mut self) -> Result<ASTNode, ParseError> 
+    { 
+        self.next_token();
+        self.nesting += 1;
+        self.skip_newlines();
+        let node = self.parse_expr()?;
+        self.expect_token(TypeOfToken::BLOCKDELIMITERS, ")")?;
+        self.nesting -= 1;
+        Ok(node)
+    }
+
+    fn parse_list(&mut self, open: Token) -> Result<ASTNode, ParseError> 
+    { 
+        let (elements, close) = self.parse_expressions("]")?;
+        Ok(ASTNode::List(ListNode::new(elements, open.span.merge(close))))
+    }
+
+    fn parse_once(&mut self, keyword: Token) -> Result<ASTNode, ParseError> 
+    { 
+        self.next_token();
+        let body = self.parse_block()?;
+        let span = keyword.span.merge(body.span());
+        Ok(ASTNode::Once(OnceNode::new(self.next_once_id(), body, span)))
+    }
+
+    fn parse_postfix(&mut self, mut node: ASTNode) -> Result<ASTNode, ParseError> 
+    { 
+        while self.peek_next_token().tot == TypeOfToken::BLOCKDELIMITERS 
+        {
+            if self.peek_next_token().value == "(" 
+            {
+                let (arguments, close) = self.parse_expressions(")")?;
+                let span = node.span().merge(close);
+                node = ASTNode::Call(CallNode::new(node, arguments, span));
+            }
+            else if self.peek_next_token().value == "[" 
+            {
+                node = self.parse_index(node)?;
+            }
+            else 
+            {
+                break;
+            }
+        }
+        Ok(node)
+    }
+
+    fn parse_index(&mut self, node: ASTNode) -> Result<ASTNode, ParseError> 
+    { 
+        self.next_token();
+        self.nesting += 1;
+        self.skip_newlines();
+        let index = self.parse_expr()?;
+        let close = self.expect_token(TypeOfToken::BLOCKDELIMITERS, "]")?;
+        self.nesting -= 1;
+        let span = node.span().merge(close.span);
+        Ok(ASTNode::Index(IndexNode::new(node, index, span)))
+    }
+
+    fn parse_interpolation(&mut self, start: Token) -> Result<ASTNode, ParseError> 
+    {
+        let mut parts = Vec::new();
+        loop 
+        {
+            let part = self.next_token();
+            match part.tot 
+            {
+                TypeOfToken::STRING => parts.push(ASTNode::String(StringNode::new(part))),
+                TypeOfToken::BLOCKDELIMITERS if part.value == "{" => 
+                {
+                    parts.push(self.parse_expr()?);
+                    self.expect_token(TypeOfToken::BLOCKDELIMITERS, "}")?;
+                }
+                TypeOfToken::INTERPOLATIONEND => 
+                {
+                    let span = start.span.merge(part.span);
+                    return Ok(ASTNode::Interpolation(InterpolationNode::new(parts, span)));
+                }
+                _ => return Err(ParseError::unexpected(&part, "'{' or the end of the string")),
+            }
+        }
+    }
+
+    fn expect_type(&mut self, expected_type: TypeOfToken, description: &str) -> Result<Token, ParseError> 
+    {
+        if self.peek_next_token().tot != expected_type 
+        {
+            return Err(ParseError::unexpected(self.peek_next_token(), description));
+        }
+        Ok(self.next_token())
+    }
+
+    fn expect_token(&mut self, expected_type: TypeOfToken, expected_value: &str) -> Result<Token, ParseError> 
+    {
+        let token = self.peek_next_token();
         if token.tot != expected_type || token.value != expected_value 
         {
-            panic!("Expected token: {} {}, but got {} {}", expected_type.to_string(), expected_value, token.tot.to_string(), token.value);
+            return Err(ParseError::unexpected(token, &format!("'{}'", expected_value)));
         }
+        Ok(self.next_token())
     }
 }
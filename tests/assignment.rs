@@ -0,0 +1,96 @@
+mod common;
+
+use common::{output, parse_errors, runtime_error};
+use lema::interpreter::RuntimeErrorKind;
+use lema::parser::ParseErrorKind;
+
+#[test]
+fn variables_can_be_reassigned()
+{
+    let src = "
+var x = 1
+x = \"one\"
+print x
+var a = 0
+var b = 0
+a = b = 7
+print \"{a} {b}\"
+print (x = 2)
+";
+    assert_eq!(output(src), "one\n7 7\n2\n");
+}
+
+#[test]
+fn compound_assignment_applies_the_operator()
+{
+    let src = "
+var x = 10
+x += 5
+print x
+x -= 3
+print x
+x *= 2
+print x
+x /= 8
+print x
+var s = \"ab\"
+s += \"c\"
+print s
+";
+    assert_eq!(output(src), "15\n12\n24\n3\nabc\n");
+}
+
+#[test]
+fn prefix_and_postfix_updates_return_different_values()
+{
+    let src = "
+var i = 5
+print i++
+print i
+print ++i
+print i--
+print --i
+print i
+";
+    assert_eq!(output(src), "5\n6\n7\n7\n5\n5\n");
+}
+
+#[test]
+fn elements_and_entries_are_assignment_targets()
+{
+    let src = "
+var xs = [1, 2, 3]
+xs[1] = 20
+xs[2] += 1
+print xs[0]++
+print xs
+var m = {\"a\": 1}
+m[\"a\"] *= 3
+m[\"b\"] = 2
+++m[\"b\"]
+print m
+";
+    assert_eq!(output(src), "1\n[2, 20, 4]\n{\"a\": 3, \"b\": 3}\n");
+}
+
+#[test]
+fn assigning_an_undeclared_variable_is_an_error()
+{
+    let error = runtime_error("x = 1\n");
+    assert_eq!(error.kind, RuntimeErrorKind::UndeclaredAssignment("x".to_string()));
+    assert_eq!(error.span.to_string(), "1:1");
+    let error = runtime_error("if true { var y = 1 }\ny += 1\n");
+    assert_eq!(error.kind, RuntimeErrorKind::UndefinedVariable("y".to_string()));
+    let error = runtime_error("var m = {}\nm[\"a\"] += 1\n");
+    assert_eq!(error.kind, RuntimeErrorKind::MissingKey("\"a\"".to_string()));
+}
+
+#[test]
+fn only_variables_elements_and_entries_can_be_assigned()
+{
+    for src in ["1 = 2\n", "f() = 1\n", "(a + b) += 1\n", "5++\n", "--\"s\"\n"]
+    {
+        let kinds: Vec<ParseErrorKind> = parse_errors(src).into_iter().map(|error| error.kind).collect();
+        assert_eq!(kinds, vec![ParseErrorKind::InvalidAssignmentTarget], "{}", src);
+    }
+}
@@ -0,0 +1,73 @@
+mod common;
+
+use common::output;
+
+#[test]
+fn each_closure_from_a_factory_has_its_own_state()
+{
+    let src = "
+define counter() {
+    var count = 0
+    return define () {
+        count += 1
+        return count
+    }
+}
+var a = counter()
+var b = counter()
+print a()
+print a()
+print b()
+print a()
+";
+    assert_eq!(output(src), "1\n2\n1\n3\n");
+}
+
+#[test]
+fn closures_see_later_changes_to_captured_variables()
+{
+    let src = "
+var greeting = \"hello\"
+define greet(name) {
+    return \"{greeting} {name}\"
+}
+print greet(\"a\")
+greeting = \"bye\"
+print greet(\"a\")
+";
+    assert_eq!(output(src), "hello a\nbye a\n");
+}
+
+#[test]
+fn closures_share_the_variables_they_capture()
+{
+    let src = "
+define pair() {
+    var value = 0
+    var set = define void (x) { value = x }
+    var get = define () { return value }
+    return [set, get]
+}
+var p = pair()
+p[0](7)
+print p[1]()
+";
+    assert_eq!(output(src), "7\n");
+}
+
+#[test]
+fn functions_can_be_passed_and_returned()
+{
+    let src = "
+define compose(f, g) {
+    return define (x) { return f(g(x)) }
+}
+define twice(f) {
+    return compose(f, f)
+}
+var inc = define (x) { return x + 1 }
+print twice(inc)(5)
+print compose(twice(inc), define (x) { return x * 10 })(1)
+";
+    assert_eq!(output(src), "7\n12\n");
+}
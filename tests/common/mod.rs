@@ -0,0 +1,80 @@
+#![allow(dead_code)]
+
+use lema::interpreter::{Interpreter, RuntimeError, Value};
+use lema::lexer::{Lexer, Token, TypeOfToken};
+use lema::parser::{ASTNode, ParseError, Parser};
+use std::fmt;
+use std::fs;
+use std::process::{self, Command};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+pub fn describe<E: fmt::Display>(errors: &[E]) -> String
+{
+    errors.iter().map(|error| error.to_string()).collect::<Vec<_>>().join("\n")
+}
+
+pub fn tokens(src: &str) -> Vec<Token>
+{
+    match Lexer::new(src.to_string()).tokenize() 
+    {
+        Ok(tokens) => tokens,
+        Err(errors) => panic!("{}", describe(&errors)),
+    }
+}
+
+pub fn values(tokens: &[Token], tot: TypeOfToken) -> Vec<String>
+{
+    tokens.iter().filter(|token| token.tot == tot).map(|token| token.value.clone()).collect()
+}
+
+pub fn parse(src: &str) -> Vec<ASTNode>
+{
+    match Parser::new(Lexer::new(src.to_string())).parse() 
+    {
+        Ok(ast) => ast,
+        Err(errors) => panic!("{}", describe(&errors)),
+    }
+}
+
+pub fn parse_errors(src: &str) -> Vec<ParseError>
+{
+    Parser::new(Lexer::new(src.to_string())).parse().err().unwrap_or_default()
+}
+
+/// Runs `src` with the `lema` binary.
+pub fn execute(src: &str) -> process::Output
+{
+    static RUNS: AtomicUsize = AtomicUsize::new(0);
+    let run = RUNS.fetch_add(1, Ordering::Relaxed);
+    let path = std::env::temp_dir().join(format!("lema-test-{}-{}.lm", process::id(), run));
+    fs::write(&path, src).unwrap();
+    let result = Command::new(env!("CARGO_BIN_EXE_lema")).arg(&path).output().unwrap();
+    fs::remove_file(&path).unwrap();
+    result
+}
+
+/// Runs `src` with the `lema` binary and returns what it printed.
+pub fn output(src: &str) -> String
+{
+    let result = execute(src);
+    assert!(result.status.success(), "{}", String::from_utf8_lossy(&result.stderr));
+    String::from_utf8(result.stdout).unwrap()
+}
+
+pub fn run(src: &str) -> Vec<Value>
+{
+    match Interpreter::new(parse(src)).interpret() 
+    {
+        Ok(values) => values,
+        Err(error) => panic!("{}", error),
+    }
+}
+
+pub fn runtime_error(src: &str) -> RuntimeError
+{
+    match Interpreter::new(parse(src)).interpret() 
+    {
+        Ok(values) => panic!("expected a runtime error, got {:?}", values),
+        Err(error) => error,
+    }
+}
@@ -0,0 +1,52 @@
+mod common;
+
+use common::{run, runtime_error};
+use lema::interpreter::{RuntimeErrorKind, Value};
+
+fn truths(src: &str) -> Vec<bool>
+{
+    run(src).into_iter()
+        .map(|value| match value 
+        {
+            Value::Bool(truth) => truth,
+            other => panic!("expected a Bool, got {:?}", other),
+        })
+        .collect()
+}
+
+#[test]
+fn numbers_compare_by_value()
+{
+    let src = "1 < 2\n2 < 1\n2 > 1\n1 <= 1\n2 <= 1\n1 >= 1\n-1 >= 0\n1.5 > 1\n";
+    assert_eq!(truths(src), [true, false, true, true, false, true, false, true]);
+}
+
+#[test]
+fn strings_compare_lexicographically()
+{
+    let src = "\"a\" < \"b\"\n\"b\" < \"a\"\n\"ab\" < \"b\"\n\"a\" < \"ab\"\n\"B\" < \"a\"\n\"x\" <= \"x\"\n\"é\" > \"z\"\n";
+    assert_eq!(truths(src), [true, false, true, true, true, true, true]);
+}
+
+#[test]
+fn equality_works_across_types()
+{
+    let src = "1 == 1\n1 == \"1\"\n\"a\" != \"a\"\ntrue == true\n[1, 2] == [1, 2]\n[1] != [1, 2]\n({\"a\": 1} == {\"a\": 1})\n1 != true\n";
+    assert_eq!(truths(src), [true, false, false, true, true, true, true, true]);
+}
+
+#[test]
+fn ordering_incomparable_types_is_an_error()
+{
+    let cases = [
+        ("1 < \"2\"\n", "<", "Number", "String"),
+        ("\"a\" >= 1\n", ">=", "String", "Number"),
+        ("true > false\n", ">", "Bool", "Bool"),
+        ("[1] <= [2]\n", "<=", "List", "List"),
+    ];
+    for (src, operator, left, right) in cases
+    {
+        let error = runtime_error(src);
+        assert_eq!(error.kind, RuntimeErrorKind::UnsupportedOperands { operator: operator.to_string(), left, right }, "{}", src);
+    }
+}
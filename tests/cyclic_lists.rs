@@ -0,0 +1,30 @@
+use lema::interpreter::Value;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+fn self_containing() -> Value
+{
+    let items = Rc::new(RefCell::new(vec![Value::Number(1.0)]));
+    items.borrow_mut().push(Value::List(Rc::clone(&items)));
+    Value::List(items)
+}
+
+#[test]
+fn cycles_display_as_ellipsis()
+{
+    let list = self_containing();
+    assert_eq!(list.to_string(), "[1, [...]]");
+    let outer = Value::List(Rc::new(RefCell::new(vec![list.clone(), list.clone()])));
+    assert_eq!(outer.to_string(), "[[1, [...]], [1, [...]]]");
+}
+
+#[test]
+fn cycles_compare_without_overflowing()
+{
+    let (left, right) = (self_containing(), self_containing());
+    assert!(left == left.clone());
+    assert!(left == right);
+    let Value::List(items) = &right else { unreachable!() };
+    items.borrow_mut()[0] = Value::Number(2.0);
+    assert!(left != right);
+}
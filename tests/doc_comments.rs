@@ -0,0 +1,39 @@
+mod common;
+
+use common::parse;
+use lema::parser::ASTNode;
+
+fn variable_docs(src: &str) -> Vec<(String, Option<String>)>
+{
+    parse(src).into_iter()
+        .filter_map(|node| match node 
+        {
+            ASTNode::Variable(variable) => Some((variable.name, variable.doc)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn leading_doc_comments_attach_to_the_next_declaration()
+{
+    let docs = variable_docs("## The answer.\n## Always.\nvar answer = 42\nvar other = 1\n");
+    assert_eq!(docs, vec![
+        ("answer".to_string(), Some("The answer.\nAlways.".to_string())),
+        ("other".to_string(), None),
+    ]);
+}
+
+#[test]
+fn trailing_doc_comments_are_plain_comments()
+{
+    let docs = variable_docs("var x = 1 ## note\nvar y = 2\n");
+    assert_eq!(docs, vec![("x".to_string(), None), ("y".to_string(), None)]);
+}
+
+#[test]
+fn doc_comments_inside_expressions_and_before_else()
+{
+    parse("var xs = [\n    1, ## first\n    ## second\n    2,\n]\n");
+    parse("if true {\n    print 1\n}\n## between\nelse {\n    print 2\n}\n");
+}
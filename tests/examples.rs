@@ -0,0 +1,24 @@
+mod common;
+
+use common::execute;
+use std::fs;
+
+#[test]
+fn examples_print_their_expected_output()
+{
+    let mut checked = 0;
+    for entry in fs::read_dir("examples").unwrap() 
+    {
+        let path = entry.unwrap().path();
+        if path.extension().is_none_or(|extension| extension != "lm") 
+        {
+            continue;
+        }
+        let expected = fs::read_to_string(path.with_extension("out")).unwrap_or_else(|_| panic!("{:?} has no .out file", path));
+        let result = execute(&fs::read_to_string(&path).unwrap());
+        assert!(result.status.success(), "{:?}: {}", path, String::from_utf8_lossy(&result.stderr));
+        assert_eq!(String::from_utf8_lossy(&result.stdout), expected, "{:?}", path);
+        checked += 1;
+    }
+    assert!(checked > 0);
+}
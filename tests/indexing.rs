@@ -0,0 +1,61 @@
+mod common;
+
+use common::{output, runtime_error};
+use lema::interpreter::RuntimeErrorKind;
+
+#[test]
+fn indexes_count_from_zero_and_from_the_end()
+{
+    let src = "var xs = [10, 20, 30]\nprint xs[0]\nprint xs[2]\nprint xs[-1]\nprint xs[-3]\n";
+    assert_eq!(output(src), "10\n30\n30\n10\n");
+}
+
+#[test]
+fn ranges_slice_lists()
+{
+    let src = "
+var xs = [0, 1, 2, 3, 4]
+print xs[1..3]
+print xs[1..=3]
+print xs[-2..5]
+print xs[0..5 step 2]
+print xs[2..2]
+";
+    assert_eq!(output(src), "[1, 2]\n[1, 2, 3]\n[3, 4]\n[0, 2, 4]\n[]\n");
+}
+
+#[test]
+fn elements_can_be_assigned()
+{
+    let src = "
+var xs = [1, [2, 3]]
+xs[0] = 5
+xs[-1][0] += 10
+var alias = xs
+alias[1][1] = \"x\"
+print xs
+";
+    assert_eq!(output(src), "[5, [12, \"x\"]]\n");
+}
+
+#[test]
+fn indexes_outside_the_list_are_errors()
+{
+    let error = runtime_error("var xs = [1, 2, 3]\nxs[3]\n");
+    assert_eq!(error.kind, RuntimeErrorKind::IndexOutOfBounds { index: 3.0, length: 3 });
+    assert_eq!(error.span.to_string(), "2:4");
+    let error = runtime_error("var xs = [1, 2, 3]\nxs[-4] = 0\n");
+    assert_eq!(error.kind, RuntimeErrorKind::IndexOutOfBounds { index: -4.0, length: 3 });
+    let error = runtime_error("var xs = [1, 2, 3]\nxs[1..5]\n");
+    assert_eq!(error.kind, RuntimeErrorKind::IndexOutOfBounds { index: 3.0, length: 3 });
+}
+
+#[test]
+fn invalid_indexes_are_errors()
+{
+    assert_eq!(runtime_error("[1, 2][0.5]\n").kind, RuntimeErrorKind::FractionalIndex(0.5));
+    assert_eq!(runtime_error("var xs = [1, 2]\nxs[1.5] = 0\n").kind, RuntimeErrorKind::FractionalIndex(1.5));
+    assert_eq!(runtime_error("[1, 2][\"0\"]\n").kind, RuntimeErrorKind::InvalidIndex("String"));
+    assert_eq!(runtime_error("var n = 1\nn[0]\n").kind, RuntimeErrorKind::NotIndexable("Number"));
+    assert_eq!(runtime_error("var xs = [1, 2, 3]\nxs[0..2] = [4, 5]\n").kind, RuntimeErrorKind::SliceAssignment);
+}
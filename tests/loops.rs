@@ -0,0 +1,121 @@
+mod common;
+
+use common::{output, parse_errors};
+use lema::parser::ParseErrorKind;
+
+fn error_kinds(src: &str) -> Vec<ParseErrorKind>
+{
+    parse_errors(src).into_iter().map(|error| error.kind).collect()
+}
+
+#[test]
+fn unlabelled_break_leaves_the_innermost_loop()
+{
+    let src = "
+while true {
+    while true {
+        print 1
+        break
+        print 2
+    }
+    print 3
+    break
+}
+print 4
+";
+    assert_eq!(output(src), "1\n3\n4\n");
+}
+
+#[test]
+fn labelled_break_leaves_outer_loops()
+{
+    let src = "
+outer: while true {
+    middle: while true {
+        while true {
+            print 1
+            break middle
+        }
+        print 2
+    }
+    print 3
+    while true {
+        break outer
+    }
+    print 4
+}
+print 5
+";
+    assert_eq!(output(src), "1\n3\n5\n");
+}
+
+#[test]
+fn jumps_in_for_loops_target_the_innermost_loop()
+{
+    let src = "
+for i in 0..3 {
+    for j in 0..4 {
+        if j == 1 { continue }
+        if j == 3 { break }
+        print \"{i}{j}\"
+    }
+}
+";
+    assert_eq!(output(src), "00\n02\n10\n12\n20\n22\n");
+}
+
+#[test]
+fn labelled_jumps_cross_while_and_for_loops()
+{
+    let src = "
+rows: for row in 1..=3 {
+    while true {
+        for column in 1..=3 {
+            if column == 2 { continue rows }
+            if row == 3 { break rows }
+            print \"{row}{column}\"
+        }
+    }
+}
+print \"done\"
+";
+    assert_eq!(output(src), "11\n21\ndone\n");
+}
+
+#[test]
+fn labelled_jumps_cross_foreach_loops()
+{
+    let src = "
+words: foreach word in \"abc\" {
+    letters: foreach letter in \"xyz\" {
+        for n in 0..2 {
+            if letter == \"y\" { continue letters }
+            if word == \"c\" { break words }
+            if letter == \"z\" and n == 1 { continue words }
+            print \"{word}{letter}{n}\"
+        }
+    }
+    print \"never\"
+}
+";
+    assert_eq!(output(src), "ax0\nax1\naz0\nbx0\nbx1\nbz0\n");
+}
+
+#[test]
+fn jumps_outside_loops_are_rejected()
+{
+    assert_eq!(error_kinds("break\n"), vec![ParseErrorKind::OutsideLoop("break".to_string())]);
+    assert_eq!(error_kinds("if true { continue }\n"), vec![ParseErrorKind::OutsideLoop("continue".to_string())]);
+    let in_function = "while true {\n    define stop() {\n        break\n    }\n}\n";
+    assert_eq!(error_kinds(in_function), vec![ParseErrorKind::OutsideLoop("break".to_string())]);
+}
+
+#[test]
+fn unknown_labels_are_rejected()
+{
+    assert_eq!(error_kinds("outer: while true { break inner }\n"), vec![ParseErrorKind::UnknownLabel("inner".to_string())]);
+    let finished = "first: while false { }\nwhile true { continue first }\n";
+    assert_eq!(error_kinds(finished), vec![ParseErrorKind::UnknownLabel("first".to_string())]);
+    let in_function = "outer: while true {\n    define stop() {\n        while true { break outer }\n    }\n}\n";
+    assert_eq!(error_kinds(in_function), vec![ParseErrorKind::UnknownLabel("outer".to_string())]);
+}
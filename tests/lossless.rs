@@ -0,0 +1,77 @@
+use lema::lexer::{self, LexErrorKind, Lexer, TypeOfToken};
+use std::fs;
+
+fn round_trip(src: &str) -> Vec<lexer::Token>
+{
+    let (tokens, _) = Lexer::new(src.to_string()).tokenize_lossless();
+    assert_eq!(lexer::reconstruct(&tokens), src);
+    tokens
+}
+
+#[test]
+fn examples_round_trip()
+{
+    for entry in fs::read_dir("examples").unwrap() 
+    {
+        let path = entry.unwrap().path();
+        if path.extension().is_none_or(|extension| extension != "lm") 
+        {
+            continue;
+        }
+        let src = fs::read_to_string(&path).unwrap();
+        let (tokens, errors) = Lexer::new(src.clone()).tokenize_lossless();
+        assert!(errors.is_empty(), "{:?}: {:?}", path, errors);
+        assert_eq!(lexer::reconstruct(&tokens), src, "{:?}", path);
+    }
+}
+
+#[test]
+fn errors_become_tokens()
+{
+    let src = "var a = 1 @ 2\nvar b = 0x\nvar c = \"open\n#[ unterminated";
+    let (tokens, errors) = Lexer::new(src.to_string()).tokenize_lossless();
+    assert_eq!(lexer::reconstruct(&tokens), src);
+    let kinds: Vec<LexErrorKind> = errors.into_iter().map(|error| error.kind).collect();
+    assert_eq!(kinds, vec![
+        LexErrorKind::UnknownCharacter('@'),
+        LexErrorKind::MalformedNumber("0x".to_string()),
+        LexErrorKind::UnterminatedString,
+        LexErrorKind::UnterminatedComment,
+    ]);
+    let error_texts: Vec<&str> = tokens.iter()
+        .filter(|token| token.tot == TypeOfToken::ERROR)
+        .map(|token| token.text.as_str())
+        .collect();
+    assert_eq!(error_texts, vec!["@", "0x", "\"open", "#[ unterminated"]);
+}
+
+#[test]
+fn escape_errors_keep_the_string_token()
+{
+    let (tokens, errors) = Lexer::new("print \"a\\qb\" + 1".to_string()).tokenize_lossless();
+    assert_eq!(errors.len(), 1);
+    assert!(tokens.iter().any(|token| token.tot == TypeOfToken::STRING && token.text == "\"a\\qb\""));
+    assert!(tokens.iter().all(|token| token.tot != TypeOfToken::ERROR));
+}
+
+#[test]
+fn interpolation_and_trivia_round_trip()
+{
+    let tokens = round_trip("var x = \"a{1 + 2}b\"   # trailing\n#[ block ]# \\\n  print x\n");
+    let comments = tokens.iter()
+        .flat_map(|token| &token.leading_trivia)
+        .filter(|trivia| trivia.kind == lexer::TriviaKind::Comment)
+        .count();
+    assert_eq!(comments, 2);
+    round_trip("print \"unclosed {1 + \n");
+    round_trip("print \"{\"\nprint 1\n");
+}
+
+#[test]
+fn segments_that_decode_to_nothing_keep_their_source()
+{
+    let src = "print \"\\q{1}\" + \"a\\q{2}\\q\"\n";
+    let (tokens, errors) = Lexer::new(src.to_string()).tokenize_lossless();
+    assert_eq!(lexer::reconstruct(&tokens), src);
+    assert_eq!(errors.len(), 3);
+}
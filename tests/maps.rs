@@ -0,0 +1,81 @@
+mod common;
+
+use common::{output, runtime_error};
+use lema::interpreter::RuntimeErrorKind;
+
+#[test]
+fn entries_keep_insertion_order()
+{
+    let src = "
+var m = {\"b\": 1, \"a\": 2, \"b\": 3}
+m[\"c\"] = 4
+m[\"a\"] = 5
+print m
+foreach pair in m { print pair }
+";
+    assert_eq!(output(src), "{\"b\": 3, \"a\": 5, \"c\": 4}\n[\"b\", 3]\n[\"a\", 5]\n[\"c\", 4]\n");
+}
+
+#[test]
+fn keys_of_different_types_are_distinct()
+{
+    let src = "
+var m = {1: \"number\", \"1\": \"string\", true: \"bool\"}
+print m[1]
+print m[\"1\"]
+print m[true]
+m[-0] = \"zero\"
+print m[0]
+print m
+";
+    assert_eq!(output(src), "number\nstring\nbool\nzero\n{1: \"number\", \"1\": \"string\", true: \"bool\", 0: \"zero\"}\n");
+}
+
+#[test]
+fn equality_ignores_entry_order()
+{
+    let src = "
+print {\"a\": 1, \"b\": 2} == {\"b\": 2, \"a\": 1}
+print {\"a\": 1} == {\"a\": 1, \"b\": 2}
+print {\"a\": 1, \"b\": 2} == {\"a\": 1, \"b\": 3}
+print {\"a\": [1, {2: 3}]} == {\"a\": [1, {2: 3}]}
+print {1: true} != {true: 1}
+";
+    assert_eq!(output(src), "true\nfalse\nfalse\ntrue\ntrue\n");
+}
+
+#[test]
+fn missing_keys_are_errors()
+{
+    let error = runtime_error("var m = {\"a\": 1}\nm[\"b\"]\n");
+    assert_eq!(error.kind, RuntimeErrorKind::MissingKey("\"b\"".to_string()));
+    assert_eq!(error.span.to_string(), "2:3");
+    let error = runtime_error("var m = {1: 1}\nm[\"1\"]\n");
+    assert_eq!(error.kind, RuntimeErrorKind::MissingKey("\"1\"".to_string()));
+    let error = runtime_error("var m = {}\nm[2]++\n");
+    assert_eq!(error.kind, RuntimeErrorKind::MissingKey("2".to_string()));
+}
+
+#[test]
+fn only_numbers_strings_and_booleans_are_keys()
+{
+    let cases = [
+        ("({[1]: 2})\n", "List"),
+        ("({\"a\": 1})[{}]\n", "Map"),
+        ("var m = {}\nm[0..2] = 1\n", "Range"),
+        ("define f() { return 1 }\n({f: 1})\n", "Function"),
+    ];
+    for (src, type_name) in cases
+    {
+        assert_eq!(runtime_error(src).kind, RuntimeErrorKind::InvalidKey(type_name), "{}", src);
+    }
+}
+
+#[test]
+fn entries_destructure_into_two_names()
+{
+    let src = "foreach (key, value) in {\"x\": 1, \"y\": 2} { print \"{key}={value}\" }\n";
+    assert_eq!(output(src), "x=1\ny=2\n");
+    let error = runtime_error("foreach (a, b, c) in {1: 2} { }\n");
+    assert_eq!(error.kind, RuntimeErrorKind::CannotDestructure { type_name: "List", count: 3 });
+}
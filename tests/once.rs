@@ -0,0 +1,88 @@
+mod common;
+
+use common::{output, parse};
+use lema::interpreter::{Interpreter, RuntimeErrorKind};
+
+#[test]
+fn once_runs_a_single_time_inside_loops()
+{
+    let src = "
+var total = 0
+for i in 0..3 {
+    var first = once {
+        print \"start\"
+        i
+    }
+    total += first
+    print i
+}
+print total
+";
+    assert_eq!(output(src), "start\n0\n1\n2\n0\n");
+}
+
+#[test]
+fn once_keeps_its_value_across_calls()
+{
+    let src = "
+define greet(name) {
+    var greeting = once {
+        print \"setup\"
+        \"hello\"
+    }
+    return \"{greeting} {name}\"
+}
+print greet(\"a\")
+print greet(\"b\")
+";
+    assert_eq!(output(src), "setup\nhello a\nhello b\n");
+}
+
+#[test]
+fn once_runs_again_after_an_early_exit()
+{
+    let src = "
+define f(x) {
+    once {
+        if x { return 1 }
+        print \"ran\"
+    }
+    return 2
+}
+print f(true)
+print f(false)
+print f(false)
+";
+    assert_eq!(output(src), "1\nran\n2\n2\n");
+}
+
+#[test]
+fn once_runs_again_after_a_break()
+{
+    let src = "
+define f(stop) {
+    while true {
+        once {
+            if stop { break }
+            print \"ran\"
+        }
+        print \"after\"
+        break
+    }
+    return 0
+}
+f(true)
+f(false)
+f(false)
+";
+    assert_eq!(output(src), "ran\nafter\nafter\n");
+}
+
+#[test]
+fn entering_a_running_once_block_is_an_error()
+{
+    let src = "define f() {\n    once { f() }\n    return 1\n}\nf()\n";
+    let error = Interpreter::new(parse(src)).interpret().expect_err("the once block calls itself");
+    assert_eq!(error.kind, RuntimeErrorKind::OnceReentered);
+    assert_eq!(error.span.to_string(), "2:5");
+}
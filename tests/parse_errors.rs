@@ -0,0 +1,63 @@
+mod common;
+
+use common::parse_errors;
+use lema::lexer::LexErrorKind;
+use lema::parser::ParseErrorKind;
+
+fn errors(src: &str) -> Vec<(ParseErrorKind, String)>
+{
+    parse_errors(src).into_iter().map(|error| (error.kind, error.span.to_string())).collect()
+}
+
+#[test]
+fn lex_errors_do_not_cascade()
+{
+    assert_eq!(errors("print \"abc\n"), vec![(ParseErrorKind::Lex(LexErrorKind::UnterminatedString), "1:7".to_string())]);
+    assert_eq!(errors("print \"\\q\" + \"ok\"\n"), vec![(ParseErrorKind::Lex(LexErrorKind::InvalidEscape("\\q".to_string())), "1:8".to_string())]);
+    assert_eq!(errors("print (1 + 0x)\nprint 2\n"), vec![(ParseErrorKind::Lex(LexErrorKind::MalformedNumber("0x".to_string())), "1:12".to_string())]);
+}
+
+#[test]
+fn errors_after_a_lex_error_are_still_reported()
+{
+    let found = errors("var x = 1 @ 2\nprint x +\n");
+    assert_eq!(found.len(), 2);
+    assert_eq!(found[0], (ParseErrorKind::Lex(LexErrorKind::UnknownCharacter('@')), "1:11".to_string()));
+    assert!(matches!(found[1].0, ParseErrorKind::UnexpectedEndOfInput { .. }));
+}
+
+#[test]
+fn end_of_input_is_reported_after_the_last_token()
+{
+    let at_end = |src: &str| 
+    {
+        let found = errors(src);
+        assert!(matches!(found[..], [(ParseErrorKind::UnexpectedEndOfInput { .. }, _)]), "{:?}", found);
+        found[0].1.clone()
+    };
+    assert_eq!(at_end("if true { print 1"), "1:18");
+    assert_eq!(at_end("if true {\n    print \"日本\""), "2:15");
+    assert_eq!(at_end("print 1 +\n"), "2:1");
+    assert_eq!(at_end("print \"\"\"a\nbc\"\"\" +"), "2:8");
+}
+
+#[test]
+fn deep_nesting_is_an_error_instead_of_a_stack_overflow()
+{
+    let nested = |open: &str, close: &str, depth: usize| format!("print {}1{}\n", open.repeat(depth), close.repeat(depth));
+    let programs = [
+        nested("(", ")", 1000),
+        nested("1 + (", ")", 1000),
+        nested("- ", "", 1000),
+        nested("[", "]", 1000),
+        nested("f(", ")", 1000),
+        format!("{}print 1\n{}", "{\n".repeat(1000), "}\n".repeat(1000)),
+    ];
+    for src in &programs
+    {
+        let found = parse_errors(src);
+        assert!(found.iter().any(|error| error.kind == ParseErrorKind::TooDeeplyNested), "{}", &src[..20]);
+    }
+    assert!(parse_errors(&nested("(", ")", 100)).is_empty());
+    assert!(parse_errors(&format!("{}print 1\n{}", "{\n".repeat(100), "}\n".repeat(100))).is_empty());
+}
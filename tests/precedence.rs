@@ -0,0 +1,54 @@
+mod common;
+
+use common::parse;
+
+fn shape(src: &str) -> String
+{
+    let nodes = parse(&format!("{}\n", src));
+    assert_eq!(nodes.len(), 1, "{}", src);
+    nodes[0].display()
+}
+
+#[test]
+fn binary_operators_bind_by_precedence()
+{
+    assert_eq!(shape("1 + 2 * 3"), "(1 + (2 * 3))");
+    assert_eq!(shape("1 * 2 + 3"), "((1 * 2) + 3)");
+    assert_eq!(shape("a or b and c"), "(a or (b and c))");
+    assert_eq!(shape("a < b == c > d"), "((a < b) == (c > d))");
+    assert_eq!(shape("1 + 2 .. 3 * 4"), "((1 + 2)..(3 * 4))");
+}
+
+#[test]
+fn left_associative_operators_group_to_the_left()
+{
+    assert_eq!(shape("a - b - c"), "((a - b) - c)");
+    assert_eq!(shape("a / b / c"), "((a / b) / c)");
+    assert_eq!(shape("a - b + c"), "((a - b) + c)");
+}
+
+#[test]
+fn right_associative_operators_group_to_the_right()
+{
+    assert_eq!(shape("2 ** 3 ** 2"), "(2 ** (3 ** 2))");
+    assert_eq!(shape("a = b = c"), "(a = (b = c))");
+    assert_eq!(shape("(a += (b = c))"), "(a += (b = c))");
+}
+
+#[test]
+fn prefix_operators_bind_by_precedence()
+{
+    assert_eq!(shape("-2 ** 2"), "-(2 ** 2)");
+    assert_eq!(shape("-a * b"), "(-a * b)");
+    assert_eq!(shape("not a == b"), "not (a == b)");
+    assert_eq!(shape("not a and b"), "(not a and b)");
+    assert_eq!(shape("- -a"), "--a");
+}
+
+#[test]
+fn updates_bind_tighter_than_arithmetic()
+{
+    assert_eq!(shape("-a++"), "-(a++)");
+    assert_eq!(shape("a++ * 2"), "((a++) * 2)");
+    assert_eq!(shape("++a * 2"), "((++a) * 2)");
+}
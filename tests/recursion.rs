@@ -0,0 +1,38 @@
+mod common;
+
+use common::{execute, output, parse};
+use lema::interpreter::{Interpreter, RuntimeErrorKind};
+
+const UNBOUNDED: [&str; 2] = [
+    "define down(n) {\n    return down(n + 1)\n}\ndown(0)\n",
+    "define down(n) {\n    foreach i in 0..1 { while true { if true { return down(n + 1) } } }\n}\ndown(0)\n",
+];
+
+#[test]
+fn deep_recursion_succeeds()
+{
+    let src = "define down(n) {\n    if n == 0 { return 0 }\n    return down(n - 1) + 1\n}\nprint down(5000)\n";
+    assert_eq!(output(src), "5000\n");
+}
+
+#[test]
+fn unbounded_recursion_reaches_the_call_limit()
+{
+    for src in UNBOUNDED
+    {
+        let result = execute(src);
+        assert_eq!(result.status.code(), Some(1), "{}", src);
+        let stderr = String::from_utf8_lossy(&result.stderr);
+        assert!(stderr.contains("Maximum call depth of 10000 exceeded"), "{}", stderr);
+    }
+}
+
+#[test]
+fn the_default_stack_budget_stops_recursion_before_the_stack_overflows()
+{
+    for src in UNBOUNDED
+    {
+        let error = Interpreter::new(parse(src)).interpret().expect_err("the recursion never ends");
+        assert_eq!(error.kind, RuntimeErrorKind::NestingTooDeep);
+    }
+}
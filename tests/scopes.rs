@@ -0,0 +1,72 @@
+mod common;
+
+use common::{output, runtime_error};
+use lema::interpreter::RuntimeErrorKind;
+
+#[test]
+fn inner_declarations_shadow_outer_ones()
+{
+    let src = "
+var x = 1
+{
+    var x = 2
+    {
+        var x = 3
+        print x
+    }
+    print x
+}
+print x
+if true { var x = 4 }
+print x
+";
+    assert_eq!(output(src), "3\n2\n1\n1\n");
+}
+
+#[test]
+fn block_bindings_are_dropped_at_block_exit()
+{
+    let error = runtime_error("{\n    var inner = 1\n}\nprint inner\n");
+    assert_eq!(error.kind, RuntimeErrorKind::UndefinedVariable("inner".to_string()));
+    assert_eq!(error.span.to_string(), "4:7");
+    let error = runtime_error("while true {\n    var seen = 1\n    break\n}\nseen\n");
+    assert_eq!(error.kind, RuntimeErrorKind::UndefinedVariable("seen".to_string()));
+    let error = runtime_error("for i in 0..2 { }\ni\n");
+    assert_eq!(error.kind, RuntimeErrorKind::UndefinedVariable("i".to_string()));
+}
+
+#[test]
+fn assignment_updates_the_nearest_declaration()
+{
+    let src = "
+var x = 1
+{
+    x = 2
+    var x = 10
+    x = 20
+}
+print x
+";
+    assert_eq!(output(src), "2\n");
+}
+
+#[test]
+fn each_loop_iteration_gets_a_fresh_environment()
+{
+    let src = "
+var getters = [0, 0, 0, 0, 0, 0]
+var i = 0
+while i < 3 {
+    var copy = i
+    getters[i] = define () { return copy }
+    i += 1
+}
+for n in 0..3 {
+    getters[n + 3] = define () { return n * 10 }
+}
+foreach getter in getters {
+    print getter()
+}
+";
+    assert_eq!(output(src), "0\n1\n2\n0\n10\n20\n");
+}
@@ -0,0 +1,127 @@
+mod common;
+
+use common::{parse, tokens, values};
+use lema::lexer::{LexErrorKind, Lexer, TypeOfToken};
+
+fn kinds(src: &str) -> Vec<(TypeOfToken, String)>
+{
+    tokens(src).into_iter()
+        .filter(|token| token.tot != TypeOfToken::EOF)
+        .map(|token| (token.tot, token.value))
+        .collect()
+}
+
+fn errors(src: &str) -> Vec<(LexErrorKind, String)>
+{
+    let (_, errors) = Lexer::new(src.to_string()).tokenize_lossless();
+    errors.into_iter().map(|error| (error.kind, error.span.to_string())).collect()
+}
+
+#[test]
+fn operators_take_the_longest_match()
+{
+    assert_eq!(values(&tokens("a==b!=c<=d>=e=f"), TypeOfToken::OPERATOR), ["==", "!=", "<=", ">=", "="]);
+    assert_eq!(values(&tokens("x=-1 ** 2 .. 3 ..= 4 += ++y--"), TypeOfToken::OPERATOR), ["=", "-", "**", "..", "..=", "+=", "++", "--"]);
+}
+
+#[test]
+fn keywords_are_not_identifiers()
+{
+    let tokens = tokens("var variable = not nothing and android");
+    assert_eq!(values(&tokens, TypeOfToken::KEYWORD), ["var", "not", "and"]);
+    assert_eq!(values(&tokens, TypeOfToken::IDENTIFIER), ["variable", "nothing", "android"]);
+}
+
+#[test]
+fn numbers_are_normalised()
+{
+    let tokens = tokens("0x1F 0b101 0o17 1_000 1.5e-3 2E3 12.25");
+    assert_eq!(values(&tokens, TypeOfToken::NUMBER), ["31", "5", "15", "1000", "0.0015", "2000", "12.25"]);
+}
+
+#[test]
+fn malformed_numbers_are_errors()
+{
+    assert_eq!(errors("0x 1e 1__0 0b2"), vec![
+        (LexErrorKind::MalformedNumber("0x".to_string()), "1:1".to_string()),
+        (LexErrorKind::MalformedNumber("1e".to_string()), "1:4".to_string()),
+        (LexErrorKind::MalformedNumber("1__0".to_string()), "1:7".to_string()),
+        (LexErrorKind::MalformedNumber("0b2".to_string()), "1:12".to_string()),
+    ]);
+    assert_eq!(errors("print 3.
+print 1.5.3 + 3.e2"), vec![
+        (LexErrorKind::MalformedNumber("3.".to_string()), "1:7".to_string()),
+        (LexErrorKind::MalformedNumber("1.5.3".to_string()), "2:7".to_string()),
+        (LexErrorKind::MalformedNumber("3.e2".to_string()), "2:15".to_string()),
+    ]);
+    assert_eq!(values(&tokens("0..3 1.5..=2"), TypeOfToken::NUMBER), ["0", "3", "1.5", "2"]);
+}
+
+#[test]
+fn prefixed_numbers_must_fit_in_64_bits()
+{
+    assert_eq!(values(&tokens("0xFFFF_FFFF_FFFF_FFFF"), TypeOfToken::NUMBER), ["18446744073709551615"]);
+    assert_eq!(errors("0xFFFFFFFFFFFFFFFFF 0b1_0000000000000000000000000000000000000000000000000000000000000000"), vec![
+        (LexErrorKind::NumberTooLarge("0xFFFFFFFFFFFFFFFFF".to_string()), "1:1".to_string()),
+        (LexErrorKind::NumberTooLarge("0b1_0000000000000000000000000000000000000000000000000000000000000000".to_string()), "1:21".to_string()),
+    ]);
+}
+
+#[test]
+fn escapes_are_decoded()
+{
+    let tokens = tokens("\"\\u{48}i\\n\\t\\\\\\\"\\{\"");
+    assert_eq!(values(&tokens, TypeOfToken::STRING), ["Hi\n\t\\\"{"]);
+    assert_eq!(errors("\"\\q\" \"\\u{D800}\""), vec![
+        (LexErrorKind::InvalidEscape("\\q".to_string()), "1:2".to_string()),
+        (LexErrorKind::InvalidEscape("\\u{D800}".to_string()), "1:7".to_string()),
+    ]);
+}
+
+#[test]
+fn raw_and_triple_quoted_strings()
+{
+    assert_eq!(kinds("r\"a\\nb{c}\""), vec![(TypeOfToken::STRING, "a\\nb{c}".to_string())]);
+    assert_eq!(kinds("\"\"\"one\n\"two\" \\t\"\"\""), vec![(TypeOfToken::STRING, "one\n\"two\" \t".to_string())]);
+}
+
+#[test]
+fn interpolation_splits_the_string()
+{
+    assert_eq!(kinds("\"a{1 + 2}b{\"c\"}\""), vec![
+        (TypeOfToken::INTERPOLATIONSTART, "\"".to_string()),
+        (TypeOfToken::STRING, "a".to_string()),
+        (TypeOfToken::BLOCKDELIMITERS, "{".to_string()),
+        (TypeOfToken::NUMBER, "1".to_string()),
+        (TypeOfToken::OPERATOR, "+".to_string()),
+        (TypeOfToken::NUMBER, "2".to_string()),
+        (TypeOfToken::BLOCKDELIMITERS, "}".to_string()),
+        (TypeOfToken::STRING, "b".to_string()),
+        (TypeOfToken::BLOCKDELIMITERS, "{".to_string()),
+        (TypeOfToken::STRING, "c".to_string()),
+        (TypeOfToken::BLOCKDELIMITERS, "}".to_string()),
+        (TypeOfToken::INTERPOLATIONEND, "\"".to_string()),
+    ]);
+}
+
+#[test]
+fn unknown_characters_and_open_strings_are_errors()
+{
+    assert_eq!(errors("1 @ 2 \"abc"), vec![
+        (LexErrorKind::UnknownCharacter('@'), "1:3".to_string()),
+        (LexErrorKind::UnterminatedString, "1:7".to_string()),
+    ]);
+    assert_eq!(errors("#[ open"), vec![(LexErrorKind::UnterminatedComment, "1:1".to_string())]);
+    assert_eq!(errors("print \"{\"\nprint 1\n"), vec![(LexErrorKind::UnterminatedString, "1:9".to_string())]);
+    assert_eq!(errors("print \"a{1 + \"x{2}\n"), vec![(LexErrorKind::UnterminatedString, "1:14".to_string())]);
+    assert_eq!(errors("print \"a{1 + \n"), vec![(LexErrorKind::UnterminatedString, "1:7".to_string())]);
+}
+
+#[test]
+fn newlines_and_semicolons_end_statements()
+{
+    assert_eq!(parse("print a\n-b\n").len(), 2);
+    assert_eq!(parse("print a; print b").len(), 2);
+    assert_eq!(parse("print (a\n- b)\n").len(), 1);
+    assert_eq!(parse("print a -\n    b\n").len(), 1);
+}
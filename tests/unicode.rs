@@ -0,0 +1,64 @@
+mod common;
+
+use common::{run, tokens, values};
+use lema::interpreter::Value;
+use lema::lexer::{LexErrorKind, Lexer, TypeOfToken};
+
+#[test]
+fn identifiers_in_any_script()
+{
+    let tokens = tokens("var café = 1\nvar 変数 = 2\nvar имя = 3\nvar λ_2 = 4\n");
+    assert_eq!(values(&tokens, TypeOfToken::IDENTIFIER), ["café", "変数", "имя", "λ_2"]);
+}
+
+#[test]
+fn strings_keep_multibyte_characters()
+{
+    let tokens = tokens("\"héllo\" \"日本\" \"😀 \\u{1F680}\"");
+    assert_eq!(values(&tokens, TypeOfToken::STRING), ["héllo", "日本", "😀 🚀"]);
+}
+
+#[test]
+fn comments_may_contain_any_characters()
+{
+    let tokens = tokens("# ünïcödé 😀\nvar a = 1 # 日本語\nvar b = #[ ñ #[ 😀 ]# ]# 2\n");
+    assert_eq!(values(&tokens, TypeOfToken::IDENTIFIER), ["a", "b"]);
+    assert_eq!(values(&tokens, TypeOfToken::NUMBER), ["1", "2"]);
+}
+
+#[test]
+fn columns_count_characters_and_offsets_count_bytes()
+{
+    let src = "var café = \"日本😀\" # ü\n  変数\n";
+    let tokens = tokens(src);
+    let spans: Vec<(&str, usize, usize)> = tokens.iter()
+        .filter(|token| token.tot != TypeOfToken::NEWLINE && token.tot != TypeOfToken::EOF)
+        .map(|token| (&src[token.span.start..token.span.end], token.span.line, token.span.column))
+        .collect();
+    assert_eq!(spans, [
+        ("var", 1, 1),
+        ("café", 1, 5),
+        ("=", 1, 10),
+        ("\"日本😀\"", 1, 12),
+        ("変数", 2, 3),
+    ]);
+}
+
+#[test]
+fn errors_after_multibyte_characters_point_at_the_right_column()
+{
+    let Err(errors) = Lexer::new("var é = \"ü\" § 1".to_string()).tokenize() else
+    {
+        panic!("the unknown character should be reported");
+    };
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].kind, LexErrorKind::UnknownCharacter('§'));
+    assert_eq!((errors[0].span.line, errors[0].span.column), (1, 13));
+}
+
+#[test]
+fn programs_run_with_unicode_names_and_text()
+{
+    let results = run("var prénom = \"Zoë\"\nvar 名前 = \"日本\"\n\"{prénom}, こんにちは {名前}\"\n");
+    assert_eq!(results.last(), Some(&Value::String("Zoë, こんにちは 日本".to_string())));
+}